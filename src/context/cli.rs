@@ -1,9 +1,13 @@
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Parser)]
 #[command(author = "lalitm1004")]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[arg(
         default_value = ".",
         value_parser = parse_path,
@@ -21,23 +25,376 @@ pub struct Cli {
     )]
     pub scale_bar: f32,
 
-    #[arg(long, action = clap::ArgAction::SetTrue, help = "Override use_color config setting")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override use_color config setting")]
     pub use_color: Option<bool>,
 
-    #[arg(long, alias = "log", action = clap::ArgAction::SetTrue, help = "Enable logarithmic scale for the bar")]
+    #[arg(long, alias = "log", num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Enable logarithmic scale for the bar")]
     pub log_scale: Option<bool>,
 
-    #[arg(long, action = clap::ArgAction::SetTrue, help = "Override respect_gitignore config setting")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override respect_gitignore config setting")]
     pub respect_gitignore: Option<bool>,
 
-    #[arg(long, action = clap::ArgAction::SetTrue, help = "Override ignore_dotfolders config setting")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override respect_ignore_files config setting")]
+    pub respect_ignore_files: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override respect_git_exclude config setting")]
+    pub respect_git_exclude: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override respect_global_gitignore config setting")]
+    pub respect_global_gitignore: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override respect_compositionignore config setting")]
+    pub respect_compositionignore: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_dotfolders config setting")]
     pub ignore_dotfolders: Option<bool>,
 
-    #[arg(long, action = clap::ArgAction::SetTrue, help = "Override ignore_dotfiles config setting")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_dotfiles config setting")]
     pub ignore_dotfiles: Option<bool>,
 
-    #[arg(long, action = clap::ArgAction::SetTrue, help = "Override ignore_empty_lines config setting")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_empty_lines config setting")]
     pub ignore_empty_lines: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_binary_files config setting")]
+    pub ignore_binary_files: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_minified_files config setting")]
+    pub ignore_minified_files: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_lfs_pointers config setting")]
+    pub ignore_lfs_pointers: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", help = "Override ignore_generated_files config setting")]
+    pub ignore_generated_files: Option<bool>,
+
+    #[arg(
+        long,
+        help = "Only count files tracked in the git index instead of walking the directory"
+    )]
+    pub git_tracked: bool,
+
+    #[arg(
+        long,
+        value_name = "REV",
+        conflicts_with = "git_tracked",
+        help = "Count the files of a git revision (commit, tag or branch) without checking it out"
+    )]
+    pub rev: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "REV",
+        conflicts_with = "rev",
+        help = "Only count files that changed since the merge base of REV and HEAD, including staged, unstaged and untracked changes"
+    )]
+    pub changed_since: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["rev", "changed_since"],
+        help = "Only count files with staged, unstaged or untracked changes"
+    )]
+    pub uncommitted: bool,
+
+    #[arg(
+        long,
+        help = "Show a table of directories by language instead of the overall composition"
+    )]
+    pub by_dir: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["by_dir", "by_package", "by_owner", "by_license", "tree"],
+        help = "Show the composition rolled up into language categories (programming, markup, data, prose)"
+    )]
+    pub by_category: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["by_dir", "by_package", "by_owner", "tree"],
+        help = "Show a table of licenses, detected from file headers, by language instead of the overall composition"
+    )]
+    pub by_license: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["by_dir", "by_package", "tree"],
+        help = "Show a table of CODEOWNERS owners by language instead of the overall composition"
+    )]
+    pub by_owner: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["by_dir", "tree"],
+        help = "Show a table of detected packages by language instead of the overall composition"
+    )]
+    pub by_package: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["by_dir", "by_package", "by_owner", "by_license", "by_category", "tree", "expand", "metric"],
+        help = "List every counted file with its language, counted lines and share of the language total"
+    )]
+    pub files: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "files",
+        help = "Only list the first N files of --files"
+    )]
+    pub top: Option<usize>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = FileOrder::Lines,
+        requires = "files",
+        help = "Order of the files listed by --files"
+    )]
+    pub sort: FileOrder,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["by_dir", "by_package", "by_owner", "by_license", "by_category", "tree", "metric"],
+        help = "Show the lines of each file extension below the language they belong to"
+    )]
+    pub expand: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "by_dir",
+        help = "Show the directory tree with counted lines and the language mix of each directory"
+    )]
+    pub tree: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Directory depth that --by-dir groups lines at [default: 1], or the deepest level shown by --tree"
+    )]
+    pub depth: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        help = "Hide directories with fewer counted lines in --tree"
+    )]
+    pub min_lines: usize,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 1.0,
+        help = "Languages with a smaller share of all lines are rolled up into an \"Other\" column"
+    )]
+    pub other_threshold: f32,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Metric::Lines,
+        conflicts_with_all = ["by_dir", "by_package", "by_owner", "by_license", "by_category", "tree"],
+        help = "Measure that drives sorting, percentages and bar widths"
+    )]
+    pub metric: Metric,
+
+    #[arg(
+        long,
+        help = "List every file that was skipped and the reason it was skipped"
+    )]
+    pub show_skipped: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Show per-language line changes between two git revisions")]
+    Diff {
+        #[arg(
+            value_name = "RANGE",
+            help = "Revision range such as main..HEAD or main...HEAD. A single revision is compared against HEAD"
+        )]
+        range: String,
+    },
+
+    #[command(about = "Show how the language composition changed over the history of a branch")]
+    History {
+        #[arg(
+            long,
+            value_name = "DATE",
+            value_parser = parse_date,
+            help = "Date of the first sample, formatted as YYYY-MM-DD"
+        )]
+        since: NaiveDate,
+
+        #[arg(
+            long,
+            value_name = "DATE",
+            value_parser = parse_date,
+            help = "Date after which no more samples are taken [default: today]"
+        )]
+        until: Option<NaiveDate>,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = HistoryStep::Monthly,
+            help = "Interval between two samples"
+        )]
+        step: HistoryStep,
+
+        #[arg(
+            long,
+            value_name = "REV",
+            default_value = "HEAD",
+            help = "Revision whose first-parent history is sampled"
+        )]
+        rev: String,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Table,
+            help = "Output format of the time series"
+        )]
+        format: OutputFormat,
+
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Also write a stacked area chart of the language shares as an SVG file"
+        )]
+        svg: Option<PathBuf>,
+    },
+
+    #[command(about = "Attribute counted lines to their authors using git blame")]
+    Authors {
+        #[arg(
+            long,
+            value_name = "REV",
+            default_value = "HEAD",
+            help = "Revision whose lines are blamed"
+        )]
+        rev: String,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Table,
+            help = "Output format of the author matrix"
+        )]
+        format: OutputFormat,
+    },
+
+    #[command(about = "Show when counted lines were last modified, per language, using git blame")]
+    Age {
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = AgePeriod::Year,
+            help = "Period that lines are grouped by"
+        )]
+        by: AgePeriod,
+
+        #[arg(
+            long,
+            value_name = "REV",
+            default_value = "HEAD",
+            help = "Revision whose lines are blamed"
+        )]
+        rev: String,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Table,
+            help = "Output format of the histogram"
+        )]
+        format: OutputFormat,
+    },
+
+    #[command(about = "Rank large files that changed often over a window of git history")]
+    Hotspots {
+        #[arg(
+            long,
+            value_name = "DATE",
+            value_parser = parse_date,
+            help = "Start of the window, formatted as YYYY-MM-DD [default: one year ago]"
+        )]
+        since: Option<NaiveDate>,
+
+        #[arg(
+            long,
+            value_name = "REV",
+            default_value = "HEAD",
+            help = "Revision whose files are ranked and whose history is read"
+        )]
+        rev: String,
+
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 20,
+            help = "Number of files to list"
+        )]
+        top: usize,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Table,
+            help = "Output format of the report"
+        )]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AgePeriod {
+    Year,
+    Quarter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FileOrder {
+    Lines,
+    Share,
+    Path,
+    Language,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HistoryStep {
+    Daily,
+    Weekly,
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Metric {
+    /// Counted lines, after the empty line and excluded pattern filters
+    Lines,
+    /// Every line, before any filtering
+    RawLines,
+    /// Non-blank lines that are not comments
+    Code,
+    /// Lines matching a comment pattern
+    Comments,
+    /// File sizes, as used by GitHub's language bar
+    Bytes,
+    /// Number of files
+    Files,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Json,
 }
 
 fn parse_path(arg: &str) -> Result<PathBuf, String> {
@@ -48,3 +405,7 @@ fn parse_path(arg: &str) -> Result<PathBuf, String> {
         Err("does not exist".into())
     }
 }
+
+fn parse_date(arg: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(arg, "%Y-%m-%d").map_err(|_| "expected a date like 2023-01-01".into())
+}
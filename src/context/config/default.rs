@@ -1,4 +1,4 @@
-use super::Tracked;
+use super::{Category, LicenseFingerprint, Tracked};
 
 pub fn use_color() -> bool {
     false
@@ -12,6 +12,22 @@ pub fn respect_gitignore() -> bool {
     true
 }
 
+pub fn respect_ignore_files() -> bool {
+    true
+}
+
+pub fn respect_git_exclude() -> bool {
+    true
+}
+
+pub fn respect_global_gitignore() -> bool {
+    true
+}
+
+pub fn respect_compositionignore() -> bool {
+    true
+}
+
 pub fn ignore_dotfolders() -> bool {
     true
 }
@@ -24,6 +40,71 @@ pub fn ignore_empty_lines() -> bool {
     true
 }
 
+pub fn ignore_binary_files() -> bool {
+    true
+}
+
+pub fn ignore_minified_files() -> bool {
+    true
+}
+
+pub fn minified_line_length() -> usize {
+    200
+}
+
+pub fn minified_whitespace_ratio() -> f32 {
+    0.1
+}
+
+pub fn ignore_lfs_pointers() -> bool {
+    true
+}
+
+pub fn ignore_generated_files() -> bool {
+    false
+}
+
+pub fn generated_header_lines() -> usize {
+    20
+}
+
+pub fn generated_header_patterns() -> Vec<String> {
+    vec![
+        r"Code generated .* DO NOT EDIT\.".into(), // go convention
+        r"@generated".into(),
+        r"(?i)this file (is|was) (automatically |auto-)generated".into(),
+        r"<auto-generated".into(), // .NET convention
+        r"Generated by the protocol buffer compiler".into(),
+    ]
+}
+
+pub fn generated_file_patterns() -> Vec<String> {
+    vec![
+        "*.pb.go".into(),
+        "*.pb.cc".into(),
+        "*.pb.h".into(),
+        "*_pb2.py".into(),
+        "*_pb2_grpc.py".into(),
+        "*.g.dart".into(),
+        "*.freezed.dart".into(),
+        "*.designer.cs".into(),
+        "*.generated.*".into(),
+    ]
+}
+
+pub fn vendored_paths() -> Vec<String> {
+    vec![
+        "**/vendor/**".into(),
+        "**/vendored/**".into(),
+        "**/third_party/**".into(),
+        "**/third-party/**".into(),
+        "**/thirdparty/**".into(),
+        "**/external/**".into(),
+        "**/extern/**".into(),
+        "**/deps/**".into(),
+    ]
+}
+
 pub fn ignored_directories() -> Vec<String> {
     vec![
         "node_modules".into(),
@@ -39,6 +120,63 @@ pub fn ignored_files() -> Vec<String> {
     vec!["package-lock.json".into()]
 }
 
+pub fn test_paths() -> Vec<String> {
+    vec![
+        "**/tests/**".into(),
+        "**/test/**".into(),
+        "**/__tests__/**".into(),
+        "**/spec/**".into(),
+        "**/*_test.go".into(),
+        "**/*_test.py".into(),
+        "**/test_*.py".into(),
+        "**/*.test.{js,jsx,ts,tsx}".into(),
+        "**/*.spec.{js,jsx,ts,tsx}".into(),
+        "**/*Test.java".into(),
+        "**/*Tests.cs".into(),
+    ]
+}
+
+pub fn license_header_lines() -> usize {
+    30
+}
+
+pub fn license_fingerprints() -> Vec<LicenseFingerprint> {
+    [
+        ("MIT", r"Permission is hereby granted, free of charge"),
+        (
+            "Apache-2.0",
+            r"Licensed under the Apache License, Version 2\.0",
+        ),
+        ("AGPL", r"GNU Affero General Public License"),
+        ("LGPL", r"GNU Lesser General Public License"),
+        ("GPL", r"GNU General Public License"),
+        ("BSD", r"Redistribution and use in source and binary forms"),
+        ("MPL-2.0", r"Mozilla Public License,? v(ersion)?\.? ?2\.0"),
+    ]
+    .into_iter()
+    .map(|(license, pattern)| LicenseFingerprint {
+        license: license.into(),
+        pattern: pattern.into(),
+        compiled_pattern: None,
+    })
+    .collect()
+}
+
+pub fn package_detectors() -> Vec<String> {
+    vec![
+        "cargo".into(),
+        "npm".into(),
+        "go".into(),
+        "python".into(),
+        "maven".into(),
+        "gradle".into(),
+    ]
+}
+
+pub fn percentage_excluded_categories() -> Vec<Category> {
+    vec![]
+}
+
 pub fn excluded_patterns() -> Vec<String> {
     vec![
         r"^\s*//".into(),                    // skip lines starting with //
@@ -50,333 +188,209 @@ pub fn excluded_patterns() -> Vec<String> {
 pub fn tracked() -> Vec<Tracked> {
     vec![
         Tracked {
-            display: "Rust".into(),
-            extensions: vec!["rs".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Python".into(),
-            extensions: vec!["py".into(), "pyi".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "HTML".into(),
-            extensions: vec!["html".into(), "htm".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "CSS".into(),
-            extensions: vec!["css".into(), "scss".into(), "sass".into(), "less".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "JavaScript".into(),
-            extensions: vec!["js".into(), "jsx".into(), "mjs".into(), "cjs".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "TypeScript".into(),
-            extensions: vec!["ts".into(), "tsx".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Svelte".into(),
-            extensions: vec!["svelte".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "C".into(),
-            extensions: vec!["c".into(), "h".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "C++".into(),
-            extensions: vec![
-                "cpp".into(),
-                "cxx".into(),
-                "cc".into(),
-                "hpp".into(),
-                "hh".into(),
-            ],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "C#".into(),
-            extensions: vec!["cs".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Bash / Shell".into(),
-            extensions: vec![
-                "sh".into(),
-                "bash".into(),
-                "bashrc".into(),
-                "bash_profile".into(),
-                "zsh".into(),
-                "zshrc".into(),
-            ],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Java".into(),
-            extensions: vec!["java".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Jupyter Notebook".into(),
-            extensions: vec!["ipynb".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Assembly".into(),
-            extensions: vec!["asm".into(), "s".into(), "mips".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Go".into(),
-            extensions: vec!["go".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Prisma".into(),
-            extensions: vec!["prisma".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Kotlin".into(),
-            extensions: vec!["kt".into(), "kts".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Swift".into(),
-            extensions: vec!["swift".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Objective-C".into(),
-            extensions: vec!["m".into(), "mm".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "PHP".into(),
-            extensions: vec!["php".into(), "phtml".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Ruby".into(),
-            extensions: vec!["rb".into(), "erb".into(), "gemspec".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Perl".into(),
-            extensions: vec!["pl".into(), "pm".into(), "t".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "R".into(),
-            extensions: vec!["r".into(), "R".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Julia".into(),
-            extensions: vec!["jl".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Scala".into(),
-            extensions: vec!["scala".into(), "sc".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Haskell".into(),
-            extensions: vec!["hs".into(), "lhs".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Elixir".into(),
-            extensions: vec!["ex".into(), "exs".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Erlang".into(),
-            extensions: vec!["erl".into(), "hrl".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "F#".into(),
-            extensions: vec!["fs".into(), "fsi".into(), "fsx".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "OCaml".into(),
-            extensions: vec!["ml".into(), "mli".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Clojure".into(),
-            extensions: vec!["clj".into(), "cljs".into(), "cljc".into(), "edn".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Dart".into(),
-            extensions: vec!["dart".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Makefile".into(),
-            extensions: vec!["mk".into(), "make".into(), "Makefile".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Dockerfile".into(),
-            extensions: vec!["dockerfile".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "YAML".into(),
-            extensions: vec!["yml".into(), "yaml".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "JSON".into(),
-            extensions: vec!["json".into(), "json5".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "TOML".into(),
-            extensions: vec!["toml".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "INI / Config".into(),
-            extensions: vec!["ini".into(), "cfg".into(), "conf".into(), "env".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Markdown".into(),
-            extensions: vec!["md".into(), "markdown".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "LaTeX".into(),
-            extensions: vec!["tex".into(), "sty".into(), "cls".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "SQL".into(),
-            extensions: vec!["sql".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "GraphQL".into(),
-            extensions: vec!["graphql".into(), "gql".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Vue".into(),
-            extensions: vec!["vue".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
-        Tracked {
-            display: "Sass".into(),
-            extensions: vec!["sass".into()],
-            color: None,
-            excluded_patterns: vec![],
-            compiled_excluded_patterns: vec![],
-        },
+            test_block_patterns: vec![r"^\s*#\[cfg\(test\)\]".into()],
+            ..language("Rust", &["rs"], Category::Programming, C_COMMENTS)
+        },
+        language(
+            "Python",
+            &["py", "pyi"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language("HTML", &["html", "htm"], Category::Markup, HTML_COMMENTS),
+        language(
+            "CSS",
+            &["css", "scss", "sass", "less"],
+            Category::Markup,
+            C_COMMENTS,
+        ),
+        language(
+            "JavaScript",
+            &["js", "jsx", "mjs", "cjs"],
+            Category::Programming,
+            C_COMMENTS,
+        ),
+        language(
+            "TypeScript",
+            &["ts", "tsx"],
+            Category::Programming,
+            C_COMMENTS,
+        ),
+        language("Svelte", &["svelte"], Category::Markup, COMPONENT_COMMENTS),
+        language("C", &["c", "h"], Category::Programming, C_COMMENTS),
+        language(
+            "C++",
+            &["cpp", "cxx", "cc", "hpp", "hh"],
+            Category::Programming,
+            C_COMMENTS,
+        ),
+        language("C#", &["cs"], Category::Programming, C_COMMENTS),
+        language(
+            "Bash / Shell",
+            &["sh", "bash", "bashrc", "bash_profile", "zsh", "zshrc"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language("Java", &["java"], Category::Programming, C_COMMENTS),
+        language("Jupyter Notebook", &["ipynb"], Category::Programming, &[]),
+        language(
+            "Assembly",
+            &["asm", "s", "mips"],
+            Category::Programming,
+            &[r"^\s*;", r"^\s*//", r"^\s*#"],
+        ),
+        language("Go", &["go"], Category::Programming, C_COMMENTS),
+        language("Prisma", &["prisma"], Category::Data, &[r"^\s*//"]),
+        language("Kotlin", &["kt", "kts"], Category::Programming, C_COMMENTS),
+        language("Swift", &["swift"], Category::Programming, C_COMMENTS),
+        language(
+            "Objective-C",
+            &["m", "mm"],
+            Category::Programming,
+            C_COMMENTS,
+        ),
+        language(
+            "PHP",
+            &["php", "phtml"],
+            Category::Programming,
+            PHP_COMMENTS,
+        ),
+        language(
+            "Ruby",
+            &["rb", "erb", "gemspec"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language(
+            "Perl",
+            &["pl", "pm", "t"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language("R", &["r", "R"], Category::Programming, HASH_COMMENTS),
+        language("Julia", &["jl"], Category::Programming, HASH_COMMENTS),
+        language("Scala", &["scala", "sc"], Category::Programming, C_COMMENTS),
+        language(
+            "Haskell",
+            &["hs", "lhs"],
+            Category::Programming,
+            &[r"^\s*--", r"^\s*\{-"],
+        ),
+        language(
+            "Elixir",
+            &["ex", "exs"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language(
+            "Erlang",
+            &["erl", "hrl"],
+            Category::Programming,
+            PERCENT_COMMENTS,
+        ),
+        language(
+            "F#",
+            &["fs", "fsi", "fsx"],
+            Category::Programming,
+            &[r"^\s*//", r"^\s*\(\*"],
+        ),
+        language(
+            "OCaml",
+            &["ml", "mli"],
+            Category::Programming,
+            &[r"^\s*\(\*"],
+        ),
+        language(
+            "Clojure",
+            &["clj", "cljs", "cljc", "edn"],
+            Category::Programming,
+            &[r"^\s*;"],
+        ),
+        language("Dart", &["dart"], Category::Programming, C_COMMENTS),
+        language(
+            "Makefile",
+            &["mk", "make", "Makefile"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language(
+            "Dockerfile",
+            &["dockerfile"],
+            Category::Programming,
+            HASH_COMMENTS,
+        ),
+        language("YAML", &["yml", "yaml"], Category::Data, HASH_COMMENTS),
+        language(
+            "JSON",
+            &["json", "json5"],
+            Category::Data,
+            &[r"^\s*//", r"^\s*/\*"],
+        ),
+        language("TOML", &["toml"], Category::Data, HASH_COMMENTS),
+        language(
+            "INI / Config",
+            &["ini", "cfg", "conf", "env"],
+            Category::Data,
+            &[r"^\s*[;#]"],
+        ),
+        language(
+            "Markdown",
+            &["md", "markdown"],
+            Category::Prose,
+            HTML_COMMENTS,
+        ),
+        language(
+            "LaTeX",
+            &["tex", "sty", "cls"],
+            Category::Prose,
+            PERCENT_COMMENTS,
+        ),
+        language("SQL", &["sql"], Category::Data, &[r"^\s*--"]),
+        language(
+            "GraphQL",
+            &["graphql", "gql"],
+            Category::Data,
+            HASH_COMMENTS,
+        ),
+        language("Vue", &["vue"], Category::Markup, COMPONENT_COMMENTS),
+        language("Sass", &["sass"], Category::Markup, C_COMMENTS),
     ]
 }
 
+// comment markers shared by several built-in languages. A `*` only continues a block comment
+// when followed by whitespace, `/` or the end of the line, so `*ptr = x;` stays code
+const C_COMMENTS: &[&str] = &[r"^\s*//", r"^\s*/\*", r"^\s*\*(\s|/|$)"];
+const PHP_COMMENTS: &[&str] = &[r"^\s*//", r"^\s*/\*", r"^\s*\*(\s|/|$)", r"^\s*#([^\[]|$)"];
+const HASH_COMMENTS: &[&str] = &[r"^\s*#"];
+const HTML_COMMENTS: &[&str] = &[r"^\s*<!--"];
+const COMPONENT_COMMENTS: &[&str] = &[r"^\s*<!--", r"^\s*//", r"^\s*/\*"];
+const PERCENT_COMMENTS: &[&str] = &[r"^\s*%"];
+
+// a built-in language without a color, excluded patterns or test block patterns
+fn language(
+    display: &str,
+    extensions: &[&str],
+    category: Category,
+    comment_patterns: &[&str],
+) -> Tracked {
+    Tracked {
+        display: display.into(),
+        extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+        color: None,
+        category: Some(category),
+        comment_patterns: Some(comment_patterns.iter().map(|p| p.to_string()).collect()),
+        compiled_comment_patterns: vec![],
+        excluded_patterns: vec![],
+        compiled_excluded_patterns: vec![],
+        test_block_patterns: vec![],
+        compiled_test_block_patterns: vec![],
+    }
+}
+
 // tests to ensure default values are valid
 #[cfg(test)]
 mod default_config_tests {
-    use crate::context::config::Config;
+    use crate::context::config::{Category, Config};
 
     use std::collections::HashSet;
 
@@ -450,6 +464,30 @@ mod default_config_tests {
                     tracked.display
                 );
             }
+
+            assert_eq!(
+                tracked.comment_patterns.as_ref().map_or(0, Vec::len),
+                tracked.compiled_comment_patterns.len(),
+                "compiled_comment_patterns length mismatch for tracked '{}'",
+                tracked.display
+            );
         }
     }
+
+    #[test]
+    fn data_and_prose_formats_are_not_programming() {
+        let config = Config::default();
+        let category_of = |display: &str| {
+            config
+                .tracked
+                .iter()
+                .find(|t| t.display == display)
+                .map(|t| t.category())
+        };
+
+        assert_eq!(category_of("Rust"), Some(Category::Programming));
+        assert_eq!(category_of("JSON"), Some(Category::Data));
+        assert_eq!(category_of("YAML"), Some(Category::Data));
+        assert_eq!(category_of("Markdown"), Some(Category::Prose));
+    }
 }
@@ -1,9 +1,14 @@
 mod default;
 
 use dirs;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -16,6 +21,18 @@ pub struct Config {
     #[serde(default = "default::respect_gitignore")]
     pub respect_gitignore: bool,
 
+    #[serde(default = "default::respect_ignore_files")]
+    pub respect_ignore_files: bool,
+
+    #[serde(default = "default::respect_git_exclude")]
+    pub respect_git_exclude: bool,
+
+    #[serde(default = "default::respect_global_gitignore")]
+    pub respect_global_gitignore: bool,
+
+    #[serde(default = "default::respect_compositionignore")]
+    pub respect_compositionignore: bool,
+
     #[serde(default = "default::ignore_dotfolders")]
     pub ignore_dotfolders: bool,
 
@@ -31,6 +48,51 @@ pub struct Config {
     #[serde(default = "default::ignore_empty_lines")]
     pub ignore_empty_lines: bool,
 
+    #[serde(default = "default::ignore_binary_files")]
+    pub ignore_binary_files: bool,
+
+    #[serde(default = "default::ignore_minified_files")]
+    pub ignore_minified_files: bool,
+
+    #[serde(default = "default::minified_line_length")]
+    pub minified_line_length: usize,
+
+    #[serde(default = "default::minified_whitespace_ratio")]
+    pub minified_whitespace_ratio: f32,
+
+    #[serde(default = "default::ignore_lfs_pointers")]
+    pub ignore_lfs_pointers: bool,
+
+    #[serde(default = "default::ignore_generated_files")]
+    pub ignore_generated_files: bool,
+
+    #[serde(default = "default::generated_header_lines")]
+    pub generated_header_lines: usize,
+
+    #[serde(default = "default::generated_header_patterns")]
+    pub generated_header_patterns: Vec<String>,
+
+    #[serde(default = "default::generated_file_patterns")]
+    pub generated_file_patterns: Vec<String>,
+
+    #[serde(default = "default::vendored_paths")]
+    pub vendored_paths: Vec<String>,
+
+    #[serde(default = "default::test_paths")]
+    pub test_paths: Vec<String>,
+
+    #[serde(default = "default::license_header_lines")]
+    pub license_header_lines: usize,
+
+    #[serde(default = "default::license_fingerprints")]
+    pub license_fingerprints: Vec<LicenseFingerprint>,
+
+    #[serde(default = "default::package_detectors")]
+    pub package_detectors: Vec<String>,
+
+    #[serde(default = "default::percentage_excluded_categories")]
+    pub percentage_excluded_categories: Vec<Category>,
+
     #[serde(default = "default::excluded_patterns")]
     pub excluded_patterns: Vec<String>,
 
@@ -39,19 +101,93 @@ pub struct Config {
 
     #[serde(skip)]
     pub compiled_excluded_patterns: Vec<Regex>,
+
+    #[serde(skip, default = "Gitignore::empty")]
+    pub compiled_ignored_directories: Gitignore,
+
+    #[serde(skip, default = "Gitignore::empty")]
+    pub compiled_ignored_files: Gitignore,
+
+    #[serde(skip)]
+    pub compiled_generated_header_patterns: Vec<Regex>,
+
+    #[serde(skip)]
+    pub compiled_generated_file_patterns: GlobSet,
+
+    #[serde(skip)]
+    pub compiled_vendored_paths: GlobSet,
+
+    #[serde(skip)]
+    pub compiled_test_paths: GlobSet,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseFingerprint {
+    pub license: String,
+    pub pattern: String,
+
+    #[serde(skip)]
+    pub compiled_pattern: Option<Regex>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tracked {
     pub display: String,
     pub extensions: Vec<String>,
     pub color: Option<String>,
 
+    /// Falls back to the built-in category of the language with the same `display`, and to
+    /// `programming` for other languages. Use [`Tracked::category`] to read it.
+    #[serde(default)]
+    pub category: Option<Category>,
+
+    /// Lines matching any of these are comments for `--metric code` and `--metric comments`.
+    /// Falls back to the built-in patterns of the language with the same `display`, and to none
+    /// for other languages.
+    #[serde(default)]
+    pub comment_patterns: Option<Vec<String>>,
+
+    #[serde(skip)]
+    pub compiled_comment_patterns: Vec<Regex>,
+
     #[serde(default)]
     pub excluded_patterns: Vec<String>,
 
     #[serde(skip)]
     pub compiled_excluded_patterns: Vec<Regex>,
+
+    #[serde(default)]
+    pub test_block_patterns: Vec<String>,
+
+    #[serde(skip)]
+    pub compiled_test_block_patterns: Vec<Regex>,
+}
+
+impl Tracked {
+    pub fn category(&self) -> Category {
+        self.category.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    #[default]
+    Programming,
+    Markup,
+    Data,
+    Prose,
+}
+
+impl Category {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::Programming => "programming",
+            Category::Markup => "markup",
+            Category::Data => "data",
+            Category::Prose => "prose",
+        }
+    }
 }
 
 impl Config {
@@ -77,6 +213,36 @@ impl Config {
         // compile all exclude regex patterns
         config.compiled_excluded_patterns = compile_regexes(&config.excluded_patterns)?;
 
+        // compile ignored directory and file globs
+        config.compiled_ignored_directories = compile_ignore_globs(&config.ignored_directories)?;
+        config.compiled_ignored_files = compile_ignore_globs(&config.ignored_files)?;
+
+        // compile generated code markers
+        config.compiled_generated_header_patterns =
+            compile_regexes(&config.generated_header_patterns)?;
+        config.compiled_generated_file_patterns = compile_globs(&config.generated_file_patterns)?;
+
+        // compile vendored path globs
+        config.compiled_vendored_paths = compile_globs(&config.vendored_paths)?;
+
+        // compile test path globs
+        config.compiled_test_paths = compile_globs(&config.test_paths)?;
+
+        // compile license text fingerprints
+        for fingerprint in &mut config.license_fingerprints {
+            let pattern = &fingerprint.pattern;
+            fingerprint.compiled_pattern = Some(
+                Regex::new(pattern)
+                    .map_err(|_| ConfigLoadError::RegexCompileFailed(pattern.clone()))?,
+            );
+        }
+
+        // built-in languages, for entries that leave out their category or comment patterns
+        let defaults: HashMap<String, Tracked> = default::tracked()
+            .into_iter()
+            .map(|tracked| (tracked.display.clone(), tracked))
+            .collect();
+
         let mut seen_displays = HashSet::new();
         let color_regex = Regex::new(r"^#([0-9a-fA-F]{6}|[0-9a-fA-F]{3})$")
             .expect("hardcoded regex should compile");
@@ -89,18 +255,41 @@ impl Config {
                 ));
             }
 
-            if let Some(color) = &tracked.color {
-                if !color_regex.is_match(color) {
-                    return Err(ConfigLoadError::InvalidColorValue(color.clone()));
+            if let Some(color) = &tracked.color
+                && !color_regex.is_match(color)
+            {
+                return Err(ConfigLoadError::InvalidColorValue(color.clone()));
+            }
+
+            if let Some(default) = defaults.get(&tracked.display) {
+                if tracked.category.is_none() {
+                    tracked.category = default.category;
+                }
+                if tracked.comment_patterns.is_none() {
+                    tracked.comment_patterns = default.comment_patterns.clone();
                 }
             }
 
             // compile all language specific regex patterns
-            tracked.compiled_excluded_patterns = compile_regexes(&tracked.excluded_patterns)?
+            tracked.compiled_excluded_patterns = compile_regexes(&tracked.excluded_patterns)?;
+
+            // compile all language specific test block markers
+            tracked.compiled_test_block_patterns = compile_regexes(&tracked.test_block_patterns)?;
+
+            // compile all language specific comment line markers
+            tracked.compiled_comment_patterns =
+                compile_regexes(tracked.comment_patterns.as_deref().unwrap_or_default())?;
         }
 
         Ok(config)
     }
+
+    /// Whether lines of `tracked` count towards the percentage shares.
+    pub fn counts_towards_percentage(&self, tracked: &Tracked) -> bool {
+        !self
+            .percentage_excluded_categories
+            .contains(&tracked.category())
+    }
 }
 
 impl Default for Config {
@@ -109,31 +298,84 @@ impl Default for Config {
             use_color: default::use_color(),
             log_scale: default::log_scale(),
             respect_gitignore: default::respect_gitignore(),
+            respect_ignore_files: default::respect_ignore_files(),
+            respect_git_exclude: default::respect_git_exclude(),
+            respect_global_gitignore: default::respect_global_gitignore(),
+            respect_compositionignore: default::respect_compositionignore(),
             ignore_dotfolders: default::ignore_dotfolders(),
             ignored_directories: default::ignored_directories(),
             ignore_dotfiles: default::ignore_dotfiles(),
             ignored_files: default::ignored_files(),
             ignore_empty_lines: default::ignore_empty_lines(),
+            ignore_binary_files: default::ignore_binary_files(),
+            ignore_minified_files: default::ignore_minified_files(),
+            minified_line_length: default::minified_line_length(),
+            minified_whitespace_ratio: default::minified_whitespace_ratio(),
+            ignore_lfs_pointers: default::ignore_lfs_pointers(),
+            ignore_generated_files: default::ignore_generated_files(),
+            generated_header_lines: default::generated_header_lines(),
+            generated_header_patterns: default::generated_header_patterns(),
+            generated_file_patterns: default::generated_file_patterns(),
+            vendored_paths: default::vendored_paths(),
+            test_paths: default::test_paths(),
+            license_header_lines: default::license_header_lines(),
+            license_fingerprints: default::license_fingerprints(),
+            package_detectors: default::package_detectors(),
+            percentage_excluded_categories: default::percentage_excluded_categories(),
             excluded_patterns: default::excluded_patterns(),
             tracked: default::tracked(),
             compiled_excluded_patterns: Vec::new(),
+            compiled_ignored_directories: Gitignore::empty(),
+            compiled_ignored_files: Gitignore::empty(),
+            compiled_generated_header_patterns: Vec::new(),
+            compiled_generated_file_patterns: GlobSet::empty(),
+            compiled_vendored_paths: GlobSet::empty(),
+            compiled_test_paths: GlobSet::empty(),
         };
 
         // compile global regex patterns (ignore errors here, assume defaults are valid)
         config.compiled_excluded_patterns =
             compile_regexes(&config.excluded_patterns).unwrap_or_default();
 
+        // compile ignored directory and file globs
+        config.compiled_ignored_directories = compile_ignore_globs(&config.ignored_directories)
+            .unwrap_or_else(|_| Gitignore::empty());
+        config.compiled_ignored_files =
+            compile_ignore_globs(&config.ignored_files).unwrap_or_else(|_| Gitignore::empty());
+
+        // compile generated code markers
+        config.compiled_generated_header_patterns =
+            compile_regexes(&config.generated_header_patterns).unwrap_or_default();
+        config.compiled_generated_file_patterns =
+            compile_globs(&config.generated_file_patterns).unwrap_or_default();
+
+        // compile vendored path globs
+        config.compiled_vendored_paths = compile_globs(&config.vendored_paths).unwrap_or_default();
+
+        // compile test path globs
+        config.compiled_test_paths = compile_globs(&config.test_paths).unwrap_or_default();
+
+        // compile license text fingerprints
+        for fingerprint in &mut config.license_fingerprints {
+            fingerprint.compiled_pattern = Regex::new(&fingerprint.pattern).ok();
+        }
+
         // compile tracked regex patterns individually
         for tracked in &mut config.tracked {
             tracked.compiled_excluded_patterns =
                 compile_regexes(&tracked.excluded_patterns).unwrap_or_default();
+            tracked.compiled_test_block_patterns =
+                compile_regexes(&tracked.test_block_patterns).unwrap_or_default();
+            tracked.compiled_comment_patterns =
+                compile_regexes(tracked.comment_patterns.as_deref().unwrap_or_default())
+                    .unwrap_or_default();
         }
 
         config
     }
 }
 
-fn compile_regexes(excluded_patterns: &Vec<String>) -> Result<Vec<Regex>, ConfigLoadError> {
+fn compile_regexes(excluded_patterns: &[String]) -> Result<Vec<Regex>, ConfigLoadError> {
     let mut compiled_patterns = Vec::with_capacity(excluded_patterns.len());
     for pat in excluded_patterns {
         let regex =
@@ -144,12 +386,42 @@ fn compile_regexes(excluded_patterns: &Vec<String>) -> Result<Vec<Regex>, Config
     Ok(compiled_patterns)
 }
 
+fn compile_globs(patterns: &Vec<String>) -> Result<GlobSet, ConfigLoadError> {
+    let mut builder = GlobSetBuilder::new();
+    for pat in patterns {
+        let glob = GlobBuilder::new(pat)
+            .literal_separator(true)
+            .build()
+            .map_err(|_| ConfigLoadError::GlobCompileFailed(pat.clone()))?;
+        builder.add(glob);
+    }
+
+    builder
+        .build()
+        .map_err(|_| ConfigLoadError::GlobCompileFailed(patterns.join(", ")))
+}
+
+// gitignore-style globs, matched against paths relative to the scan root
+fn compile_ignore_globs(patterns: &Vec<String>) -> Result<Gitignore, ConfigLoadError> {
+    let mut builder = GitignoreBuilder::new(".");
+    for pat in patterns {
+        builder
+            .add_line(None, pat)
+            .map_err(|_| ConfigLoadError::GlobCompileFailed(pat.clone()))?;
+    }
+
+    builder
+        .build()
+        .map_err(|_| ConfigLoadError::GlobCompileFailed(patterns.join(", ")))
+}
+
 #[derive(Debug)]
 pub enum ConfigLoadError {
     FileReadFailed,
     TomlParseFailed,
     InvalidColorValue(String),
     RegexCompileFailed(String),
+    GlobCompileFailed(String),
     DuplicateTrackedDisplay(String),
 }
 
@@ -168,9 +440,99 @@ impl std::fmt::Display for ConfigLoadError {
             ConfigLoadError::RegexCompileFailed(pattern) => {
                 write!(f, "failed to compile regex pattern: '{}'", pattern)
             }
+            ConfigLoadError::GlobCompileFailed(pattern) => {
+                write!(f, "failed to compile glob pattern: '{}'", pattern)
+            }
             ConfigLoadError::DuplicateTrackedDisplay(display) => {
                 write!(f, "duplicate tracked display found: '{}'", display)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    fn load(content: &str) -> Config {
+        let path = std::env::temp_dir().join(format!(
+            "composition-config-{}-{:?}.toml",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::write(&path, content).expect("config should be writable");
+        let config = Config::load_from_path(&path);
+        let _ = std::fs::remove_file(&path);
+        config.expect("config should load")
+    }
+
+    fn category_of(config: &Config, display: &str) -> Category {
+        config
+            .tracked
+            .iter()
+            .find(|t| t.display == display)
+            .map(Tracked::category)
+            .unwrap()
+    }
+
+    #[test]
+    fn missing_category_falls_back_to_the_built_in_language() {
+        let config = load(
+            r#"
+[[tracked]]
+display = "JSON"
+extensions = ["json"]
+
+[[tracked]]
+display = "Markdown"
+extensions = ["md"]
+
+[[tracked]]
+display = "YAML"
+extensions = ["yml"]
+category = "prose"
+
+[[tracked]]
+display = "Zig"
+extensions = ["zig"]
+"#,
+        );
+
+        assert_eq!(category_of(&config, "JSON"), Category::Data);
+        assert_eq!(category_of(&config, "Markdown"), Category::Prose);
+        assert_eq!(category_of(&config, "YAML"), Category::Prose);
+        assert_eq!(category_of(&config, "Zig"), Category::Programming);
+    }
+
+    #[test]
+    fn missing_comment_patterns_fall_back_to_the_built_in_language() {
+        let config = load(
+            r#"
+[[tracked]]
+display = "Rust"
+extensions = ["rs"]
+
+[[tracked]]
+display = "Python"
+extensions = ["py"]
+comment_patterns = []
+
+[[tracked]]
+display = "Zig"
+extensions = ["zig"]
+"#,
+        );
+
+        let compiled_count = |display: &str| {
+            config
+                .tracked
+                .iter()
+                .find(|t| t.display == display)
+                .map(|t| t.compiled_comment_patterns.len())
+                .unwrap()
+        };
+        assert!(compiled_count("Rust") > 0);
+        assert_eq!(compiled_count("Python"), 0);
+        assert_eq!(compiled_count("Zig"), 0);
+    }
+}
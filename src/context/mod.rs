@@ -3,32 +3,97 @@ pub mod config;
 
 use std::path::PathBuf;
 
-use cli::Cli;
+use cli::{Cli, Command, FileOrder, Metric};
 use config::Config;
 
 #[derive(Debug)]
 pub struct AppContext {
+    pub command: Option<Command>,
     pub path: PathBuf,
     pub scale_bar: f32,
+    pub show_skipped: bool,
+    pub git_tracked: bool,
+    pub revision: Option<String>,
+    pub changed_since: Option<String>,
+    pub directory_depth: Option<usize>,
+    pub by_package: bool,
+    pub by_owner: bool,
+    pub by_license: bool,
+    pub by_category: bool,
+    pub expand: bool,
+    pub metric: Metric,
+    pub list_files: bool,
+    pub top_files: Option<usize>,
+    pub file_order: FileOrder,
+    pub tree: bool,
+    pub tree_depth: Option<usize>,
+    pub min_lines: usize,
+    pub other_threshold: f32,
     pub config: Config,
     pub config_loaded: bool,
 }
 
 impl AppContext {
     pub fn from_cli(cli: Cli) -> Self {
-        let (mut config, config_loaded) = Config::from_config();
+        let (config, config_loaded) = Config::from_config();
+        Self::with_config(cli, config, config_loaded)
+    }
 
+    /// Like [`AppContext::from_cli`], but with the given config instead of the user's
+    /// `config.toml`.
+    pub fn with_config(cli: Cli, mut config: Config, config_loaded: bool) -> Self {
         // apply CLI overrides to config
         Self::apply_cli_overrides(&mut config, &cli);
 
         AppContext {
+            command: cli.command,
             path: cli.path,
             scale_bar: cli.scale_bar,
+            show_skipped: cli.show_skipped,
+            git_tracked: cli.git_tracked,
+            revision: cli.rev,
+            // uncommitted changes are the changes since HEAD
+            changed_since: cli
+                .changed_since
+                .or_else(|| cli.uncommitted.then(|| "HEAD".to_string())),
+            // the tree and package views need every directory, and aggregate them themselves
+            directory_depth: if cli.by_dir {
+                Some(cli.depth.unwrap_or(1) as usize)
+            } else if cli.tree || cli.by_package {
+                Some(usize::MAX)
+            } else {
+                None
+            },
+            by_package: cli.by_package,
+            by_owner: cli.by_owner,
+            by_license: cli.by_license,
+            by_category: cli.by_category,
+            expand: cli.expand,
+            metric: cli.metric,
+            list_files: cli.files,
+            top_files: cli.top,
+            file_order: cli.sort,
+            tree: cli.tree,
+            tree_depth: cli.depth.map(usize::from),
+            min_lines: cli.min_lines,
+            other_threshold: cli.other_threshold,
             config,
             config_loaded,
         }
     }
 
+    /// Whether the selected view needs the line counts of every single file.
+    pub fn keeps_file_line_counts(&self) -> bool {
+        self.by_owner || self.by_license || self.list_files
+    }
+
+    /// The scan root with symlinks resolved, falling back to the path as given.
+    pub fn canonical_root(&self) -> PathBuf {
+        self.path
+            .canonicalize()
+            .unwrap_or_else(|_| self.path.clone())
+    }
+
     fn apply_cli_overrides(config: &mut Config, cli: &Cli) {
         if let Some(use_color) = cli.use_color {
             config.use_color = use_color;
@@ -42,6 +107,22 @@ impl AppContext {
             config.respect_gitignore = respect_gitignore;
         }
 
+        if let Some(respect_ignore_files) = cli.respect_ignore_files {
+            config.respect_ignore_files = respect_ignore_files;
+        }
+
+        if let Some(respect_git_exclude) = cli.respect_git_exclude {
+            config.respect_git_exclude = respect_git_exclude;
+        }
+
+        if let Some(respect_global_gitignore) = cli.respect_global_gitignore {
+            config.respect_global_gitignore = respect_global_gitignore;
+        }
+
+        if let Some(respect_compositionignore) = cli.respect_compositionignore {
+            config.respect_compositionignore = respect_compositionignore;
+        }
+
         if let Some(ignore_dotfolders) = cli.ignore_dotfolders {
             config.ignore_dotfolders = ignore_dotfolders;
         }
@@ -53,5 +134,21 @@ impl AppContext {
         if let Some(ignore_empty_lines) = cli.ignore_empty_lines {
             config.ignore_empty_lines = ignore_empty_lines;
         }
+
+        if let Some(ignore_binary_files) = cli.ignore_binary_files {
+            config.ignore_binary_files = ignore_binary_files;
+        }
+
+        if let Some(ignore_minified_files) = cli.ignore_minified_files {
+            config.ignore_minified_files = ignore_minified_files;
+        }
+
+        if let Some(ignore_lfs_pointers) = cli.ignore_lfs_pointers {
+            config.ignore_lfs_pointers = ignore_lfs_pointers;
+        }
+
+        if let Some(ignore_generated_files) = cli.ignore_generated_files {
+            config.ignore_generated_files = ignore_generated_files;
+        }
     }
 }
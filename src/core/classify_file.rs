@@ -0,0 +1,98 @@
+use crate::context::config::Config;
+
+const BINARY_SNIFF_LEN: usize = 8000;
+const LFS_POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileClass {
+    Text,
+    Binary,
+    Minified,
+    LfsPointer,
+}
+
+pub fn classify_file(content: &[u8], config: &Config) -> FileClass {
+    // git uses the same heuristic: a NUL byte early in the file means binary
+    let sniff_len = content.len().min(BINARY_SNIFF_LEN);
+    if content[..sniff_len].contains(&0) {
+        return FileClass::Binary;
+    }
+
+    if content.starts_with(LFS_POINTER_HEADER) {
+        return FileClass::LfsPointer;
+    }
+
+    if is_minified(
+        content,
+        config.minified_line_length,
+        config.minified_whitespace_ratio,
+    ) {
+        return FileClass::Minified;
+    }
+
+    FileClass::Text
+}
+
+fn is_minified(content: &[u8], line_length: usize, whitespace_ratio: f32) -> bool {
+    if content.is_empty() {
+        return false;
+    }
+
+    let line_count = content.split(|b| *b == b'\n').count();
+    let average_line_length = content.len() / line_count;
+    if average_line_length < line_length {
+        return false;
+    }
+
+    let whitespace = content.iter().filter(|b| b.is_ascii_whitespace()).count();
+    (whitespace as f32 / content.len() as f32) < whitespace_ratio
+}
+
+#[cfg(test)]
+mod classify_file_tests {
+    use super::{FileClass, classify_file};
+    use crate::context::config::Config;
+
+    #[test]
+    fn plain_source_is_text() {
+        let config = Config::default();
+        let content = b"fn main() {\n    println!(\"hello\");\n}\n";
+
+        assert_eq!(classify_file(content, &config), FileClass::Text);
+    }
+
+    #[test]
+    fn nul_byte_is_binary() {
+        let config = Config::default();
+        let content = b"\x7fELF\x02\x01\x01\x00\x00\x00";
+
+        assert_eq!(classify_file(content, &config), FileClass::Binary);
+    }
+
+    #[test]
+    fn lfs_pointer_is_detected() {
+        let config = Config::default();
+        let content = b"version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+
+        assert_eq!(classify_file(content, &config), FileClass::LfsPointer);
+    }
+
+    #[test]
+    fn single_long_line_is_minified() {
+        let config = Config::default();
+        let content = "var a=1;function b(){return a+1}".repeat(100);
+
+        assert_eq!(
+            classify_file(content.as_bytes(), &config),
+            FileClass::Minified
+        );
+    }
+
+    #[test]
+    fn long_prose_line_is_not_minified() {
+        let config = Config::default();
+        let content = "the quick brown fox jumps over the lazy dog ".repeat(100);
+
+        assert_eq!(classify_file(content.as_bytes(), &config), FileClass::Text);
+    }
+}
@@ -0,0 +1,135 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::{fs, path::Path};
+
+// the locations GitHub looks at, in the order it looks at them
+const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Ownership rules of a `CODEOWNERS` file. Patterns follow gitignore syntax and, like on GitHub,
+/// the last matching rule wins.
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    matcher: Gitignore,
+    owners: Vec<String>,
+    /// `docs/*` owns the files directly in `docs/`, but not those in its subdirectories.
+    owns_nested_files: bool,
+}
+
+impl CodeOwners {
+    /// Loads the first `CODEOWNERS` file found in the repository root, `.github/` or `docs/`.
+    pub fn load(repository_root: &Path) -> Option<Self> {
+        Self::load_with(repository_root, |location| {
+            fs::read_to_string(repository_root.join(location)).ok()
+        })
+    }
+
+    /// Like [`CodeOwners::load`], but reads each location, relative to the repository root,
+    /// through `read`.
+    pub fn load_with(
+        repository_root: &Path,
+        read: impl Fn(&Path) -> Option<String>,
+    ) -> Option<Self> {
+        CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+            let content = read(Path::new(location))?;
+            Some(CodeOwners {
+                rules: parse_rules(repository_root, &content),
+            })
+        })
+    }
+
+    /// Owners of a path relative to the repository root. A matching rule without owners leaves
+    /// the path unowned, just like no matching rule at all.
+    pub fn owners(&self, relative_path: &Path) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                let matched = if rule.owns_nested_files {
+                    rule.matcher
+                        .matched_path_or_any_parents(relative_path, false)
+                } else {
+                    rule.matcher.matched(relative_path, false)
+                };
+                matched.is_ignore()
+            })
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+}
+
+fn parse_rules(repository_root: &Path, content: &str) -> Vec<Rule> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let pattern = tokens.next()?;
+            // comments, and negations which CODEOWNERS does not support
+            if pattern.starts_with('#') || pattern.starts_with('!') {
+                return None;
+            }
+
+            let mut builder = GitignoreBuilder::new(repository_root);
+            builder.add_line(None, pattern).ok()?;
+            let matcher = builder.build().ok()?;
+
+            // owners may be followed by a trailing comment
+            let owners = tokens
+                .take_while(|token| !token.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            Some(Rule {
+                matcher,
+                owners,
+                owns_nested_files: !pattern.ends_with("/*"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod codeowners_tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let codeowners = CodeOwners {
+            rules: parse_rules(
+                Path::new("/repo"),
+                "# default owners\n* @acme/core\n/docs/ @acme/docs @jane\n*.rs @acme/rust\n/docs/generated/\n",
+            ),
+        };
+
+        assert_eq!(codeowners.owners(Path::new("README.md")), ["@acme/core"]);
+        assert_eq!(
+            codeowners.owners(Path::new("docs/guide.md")),
+            ["@acme/docs", "@jane"]
+        );
+        assert_eq!(codeowners.owners(Path::new("docs/lib.rs")), ["@acme/rust"]);
+        assert!(
+            codeowners
+                .owners(Path::new("docs/generated/api.md"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn trailing_wildcard_only_owns_direct_children() {
+        let codeowners = CodeOwners {
+            rules: parse_rules(
+                Path::new("/repo"),
+                "* @acme/core\ndocs/* @acme/docs\napps/ @acme/apps\n",
+            ),
+        };
+
+        assert_eq!(codeowners.owners(Path::new("docs/lib.rs")), ["@acme/docs"]);
+        assert_eq!(
+            codeowners.owners(Path::new("docs/sub/lib.rs")),
+            ["@acme/core"]
+        );
+        assert_eq!(
+            codeowners.owners(Path::new("apps/web/src/main.ts")),
+            ["@acme/apps"]
+        );
+    }
+}
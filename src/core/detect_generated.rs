@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use crate::context::config::Config;
+
+pub fn is_generated(path: &Path, content: &str, config: &Config) -> bool {
+    // well-known generated file names, e.g. *.pb.go
+    let matches_file_pattern = path
+        .file_name()
+        .map(|name| config.compiled_generated_file_patterns.is_match(name))
+        .unwrap_or(false);
+
+    if matches_file_pattern {
+        return true;
+    }
+
+    // generator markers are placed in the first few lines of a file
+    content
+        .lines()
+        .take(config.generated_header_lines)
+        .any(|line| {
+            config
+                .compiled_generated_header_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(line))
+        })
+}
+
+#[cfg(test)]
+mod detect_generated_tests {
+    use super::is_generated;
+    use crate::context::config::Config;
+    use std::path::Path;
+
+    #[test]
+    fn go_generated_header_is_detected() {
+        let config = Config::default();
+        let content = "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n";
+
+        assert!(is_generated(Path::new("api/api.go"), content, &config));
+    }
+
+    #[test]
+    fn generated_file_name_is_detected() {
+        let config = Config::default();
+
+        assert!(is_generated(
+            Path::new("api/api.pb.go"),
+            "package api\n",
+            &config
+        ));
+    }
+
+    #[test]
+    fn marker_after_header_is_ignored() {
+        let config = Config::default();
+        let content = format!("{}// @generated\n", "let x = 1;\n".repeat(50));
+
+        assert!(!is_generated(Path::new("src/lib.rs"), &content, &config));
+    }
+}
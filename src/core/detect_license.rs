@@ -0,0 +1,114 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::context::config::{Config, Tracked};
+
+// the capture is limited to the characters of an SPDX license expression
+static SPDX_IDENTIFIER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"SPDX-License-Identifier:\s*([A-Za-z0-9.+\-() ]+)")
+        .expect("hardcoded regex should compile")
+});
+
+/// The license declared in the header of a file, either through an `SPDX-License-Identifier`
+/// tag or by a fingerprint of its license text. SPDX tags take precedence over fingerprints.
+///
+/// SPDX tags are only accepted on comment lines of `tracked`, so a tag inside a string literal
+/// does not count.
+pub fn detect_license(content: &str, config: &Config, tracked: &Tracked) -> Option<String> {
+    let header = || content.lines().take(config.license_header_lines);
+
+    let spdx_expression = header().find_map(|line| {
+        if !tracked
+            .compiled_comment_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(line))
+        {
+            return None;
+        }
+
+        let expression = SPDX_IDENTIFIER.captures(line)?.get(1)?.as_str();
+        // drop what is left of an HTML comment that closes on the same line
+        let expression = expression.trim_end().trim_end_matches('-').trim_end();
+        (!expression.is_empty()).then(|| expression.to_string())
+    });
+    if spdx_expression.is_some() {
+        return spdx_expression;
+    }
+
+    // fingerprints are tried in order, the first one found in the header wins
+    config.license_fingerprints.iter().find_map(|fingerprint| {
+        let pattern = fingerprint.compiled_pattern.as_ref()?;
+        header()
+            .any(|line| pattern.is_match(line))
+            .then(|| fingerprint.license.clone())
+    })
+}
+
+#[cfg(test)]
+mod detect_license_tests {
+    use super::detect_license;
+    use crate::context::config::{Config, Tracked};
+
+    fn tracked(config: &Config, display: &str) -> Tracked {
+        config
+            .tracked
+            .iter()
+            .find(|t| t.display == display)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn spdx_identifier_is_detected() {
+        let config = Config::default();
+        let content = "/* SPDX-License-Identifier: MIT OR Apache-2.0 */\nint main() {}\n";
+
+        assert_eq!(
+            detect_license(content, &config, &tracked(&config, "C")),
+            Some("MIT OR Apache-2.0".into())
+        );
+    }
+
+    #[test]
+    fn spdx_identifier_in_an_html_comment_is_detected() {
+        let config = Config::default();
+        let content = "<!-- SPDX-License-Identifier: (MIT AND CC-BY-4.0) -->\n# Title\n";
+
+        assert_eq!(
+            detect_license(content, &config, &tracked(&config, "Markdown")),
+            Some("(MIT AND CC-BY-4.0)".into())
+        );
+    }
+
+    #[test]
+    fn spdx_identifier_inside_a_string_is_ignored() {
+        let config = Config::default();
+        let content = "const TAG: &str = \"SPDX-License-Identifier: MIT\";\nfn main() {}\n";
+
+        assert_eq!(
+            detect_license(content, &config, &tracked(&config, "Rust")),
+            None
+        );
+    }
+
+    #[test]
+    fn license_text_fingerprint_is_detected() {
+        let config = Config::default();
+        let content = "// Copyright 2024 Acme\n//\n// Licensed under the Apache License, Version 2.0 (the \"License\");\npackage main\n";
+
+        assert_eq!(
+            detect_license(content, &config, &tracked(&config, "Go")),
+            Some("Apache-2.0".into())
+        );
+    }
+
+    #[test]
+    fn unlicensed_file_has_no_license() {
+        let config = Config::default();
+
+        assert_eq!(
+            detect_license("fn main() {}\n", &config, &tracked(&config, "Rust")),
+            None
+        );
+    }
+}
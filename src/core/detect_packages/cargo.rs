@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use super::PackageDetector;
+
+/// Cargo packages, including workspace members. Virtual workspace roots declare no package.
+pub struct Cargo;
+
+impl PackageDetector for Cargo {
+    fn name(&self) -> &'static str {
+        "cargo"
+    }
+
+    fn manifest_names(&self) -> &'static [&'static str] {
+        &["Cargo.toml"]
+    }
+
+    fn package_name(&self, _directory: &Path, manifest: &str) -> Option<String> {
+        let manifest: toml::Table = manifest.parse().ok()?;
+        let name = manifest.get("package")?.get("name")?.as_str()?;
+        Some(name.to_string())
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use super::PackageDetector;
+
+/// Go modules, named by their module path.
+pub struct Go;
+
+impl PackageDetector for Go {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn manifest_names(&self) -> &'static [&'static str] {
+        &["go.mod"]
+    }
+
+    fn package_name(&self, _directory: &Path, manifest: &str) -> Option<String> {
+        manifest.lines().find_map(|line| {
+            let module = line.trim().strip_prefix("module")?.trim();
+            (!module.is_empty()).then(|| module.trim_matches('"').to_string())
+        })
+    }
+}
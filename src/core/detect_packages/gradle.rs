@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use super::PackageDetector;
+
+/// Gradle projects. Like Gradle itself, a project is named after its directory.
+pub struct Gradle;
+
+impl PackageDetector for Gradle {
+    fn name(&self) -> &'static str {
+        "gradle"
+    }
+
+    fn manifest_names(&self) -> &'static [&'static str] {
+        &["build.gradle", "build.gradle.kts"]
+    }
+
+    fn package_name(&self, directory: &Path, _manifest: &str) -> Option<String> {
+        // a scan root such as `.` only has a name once resolved, other directories may only exist
+        // in a revision
+        let name = match directory.file_name() {
+            Some(name) => name.to_os_string(),
+            None => directory.canonicalize().ok()?.file_name()?.to_os_string(),
+        };
+        Some(name.to_string_lossy().into_owned())
+    }
+}
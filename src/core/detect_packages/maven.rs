@@ -0,0 +1,29 @@
+use regex::Regex;
+use std::{path::Path, sync::LazyLock};
+
+use super::PackageDetector;
+
+// the parent block holds the artifact id of the parent, not of the module itself
+static PARENT_BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<parent>.*?</parent>").unwrap());
+static ARTIFACT_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<artifactId>\s*([^<\s]+)\s*</artifactId>").unwrap());
+
+/// Maven modules, named by their artifact id.
+pub struct Maven;
+
+impl PackageDetector for Maven {
+    fn name(&self) -> &'static str {
+        "maven"
+    }
+
+    fn manifest_names(&self) -> &'static [&'static str] {
+        &["pom.xml"]
+    }
+
+    fn package_name(&self, _directory: &Path, manifest: &str) -> Option<String> {
+        let manifest = PARENT_BLOCK.replace(manifest, "");
+        let artifact_id = ARTIFACT_ID.captures(&manifest)?.get(1)?;
+        Some(artifact_id.as_str().to_string())
+    }
+}
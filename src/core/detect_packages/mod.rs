@@ -0,0 +1,207 @@
+mod cargo;
+mod go;
+mod gradle;
+mod maven;
+mod npm;
+mod python;
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    context::AppContext,
+    core::{ContentReader, get_all_entries::get_named_entries, git::GitError},
+};
+
+/// Recognizes the package rooted at a directory from one of its manifest files.
+///
+/// New ecosystems are supported by implementing this trait and adding the detector to `DETECTORS`.
+pub trait PackageDetector: Sync {
+    /// Name that enables the detector in the `package_detectors` config setting.
+    fn name(&self) -> &'static str;
+
+    /// File names of the manifests this detector reads.
+    fn manifest_names(&self) -> &'static [&'static str];
+
+    /// The package declared by `manifest`, or `None` if it does not declare one, like a virtual
+    /// Cargo workspace root.
+    fn package_name(&self, directory: &Path, manifest: &str) -> Option<String>;
+}
+
+const DETECTORS: &[&dyn PackageDetector] = &[
+    &cargo::Cargo,
+    &npm::Npm,
+    &go::Go,
+    &python::Python,
+    &maven::Maven,
+    &gradle::Gradle,
+];
+
+#[derive(Debug)]
+pub struct Package {
+    pub name: String,
+    /// Directory of the manifest, relative to the scan root.
+    pub root: PathBuf,
+}
+
+/// Finds the packages below the scan root, using the detectors enabled in the config.
+///
+/// Manifests are listed and read from the same source as the counted files, such as a revision
+/// or the git index, and ignored files are skipped. When a directory holds manifests of several
+/// ecosystems, the detector listed first in `package_detectors` wins.
+pub fn detect_packages(app_context: &AppContext) -> Result<Vec<Package>, GitError> {
+    let detectors: Vec<&dyn PackageDetector> = app_context
+        .config
+        .package_detectors
+        .iter()
+        .filter_map(|name| DETECTORS.iter().find(|d| d.name() == name).copied())
+        .collect();
+
+    let manifest_names: Vec<&str> = detectors
+        .iter()
+        .flat_map(|d| d.manifest_names().iter().copied())
+        .collect();
+    let mut manifests = get_named_entries(app_context, &manifest_names)?;
+    manifests.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let reader = ContentReader::new(&app_context.path);
+    let mut packages: Vec<(usize, Package)> = Vec::new();
+    for manifest in &manifests {
+        let Some(file_name) = manifest.path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((priority, detector)) = detectors
+            .iter()
+            .enumerate()
+            .find(|(_, d)| d.manifest_names().contains(&file_name))
+        else {
+            continue;
+        };
+
+        let directory = manifest.path.parent().unwrap_or(&app_context.path);
+        let root = directory
+            .strip_prefix(&app_context.path)
+            .unwrap_or(directory)
+            .to_path_buf();
+
+        // a package already found in the same directory by a detector of higher priority wins
+        if packages
+            .iter()
+            .any(|(other, package)| package.root == root && *other <= priority)
+        {
+            continue;
+        }
+
+        let Some(name) = reader
+            .read(manifest)
+            .ok()
+            .and_then(|content| String::from_utf8(content).ok())
+            .and_then(|content| detector.package_name(directory, &content))
+        else {
+            continue;
+        };
+
+        packages.retain(|(_, package)| package.root != root);
+        packages.push((priority, Package { name, root }));
+    }
+
+    Ok(packages.into_iter().map(|(_, package)| package).collect())
+}
+
+#[cfg(test)]
+mod detect_packages_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    fn package_names(repository: &TestRepository, args: &[&str]) -> Vec<(String, PathBuf)> {
+        let app_context = repository.app_context(args);
+        detect_packages(&app_context)
+            .unwrap()
+            .into_iter()
+            .map(|package| (package.name, package.root))
+            .collect()
+    }
+
+    fn cargo_manifest(name: &str) -> String {
+        format!("[package]\nname = \"{name}\"\n")
+    }
+
+    #[test]
+    fn manifests_in_ignored_directories_are_skipped() {
+        let repository = TestRepository::new();
+        repository.write(".gitignore", "build/\n");
+        repository.write("crates/alpha/Cargo.toml", &cargo_manifest("alpha"));
+        repository.write("build/copy/Cargo.toml", &cargo_manifest("copy"));
+
+        assert_eq!(
+            package_names(&repository, &[]),
+            [("alpha".to_string(), PathBuf::from("crates/alpha"))]
+        );
+    }
+
+    #[test]
+    fn manifests_are_read_from_the_counted_revision() {
+        let repository = TestRepository::new();
+        repository.write("crates/alpha/Cargo.toml", &cargo_manifest("alpha"));
+        repository.write("crates/alpha/src/lib.rs", "fn alpha() {}\n");
+        repository.commit("Alice", "2024-01-10");
+        repository.write("crates/alpha/Cargo.toml", &cargo_manifest("renamed"));
+        repository.write("crates/beta/Cargo.toml", &cargo_manifest("beta"));
+
+        assert_eq!(
+            package_names(&repository, &["--rev=HEAD"]),
+            [("alpha".to_string(), PathBuf::from("crates/alpha"))]
+        );
+        assert_eq!(
+            package_names(&repository, &["--git-tracked"]),
+            [("renamed".to_string(), PathBuf::from("crates/alpha"))]
+        );
+    }
+
+    #[test]
+    fn unchanged_manifests_still_define_packages() {
+        let repository = TestRepository::new();
+        repository.write("crates/alpha/Cargo.toml", &cargo_manifest("alpha"));
+        repository.write("crates/alpha/src/lib.rs", "fn alpha() {}\n");
+        repository.commit("Alice", "2024-01-10");
+        repository.write("crates/alpha/src/lib.rs", "fn alpha() { 2 }\n");
+
+        assert_eq!(
+            package_names(&repository, &["--changed-since=HEAD"]),
+            [("alpha".to_string(), PathBuf::from("crates/alpha"))]
+        );
+    }
+
+    #[test]
+    fn virtual_cargo_workspace_declares_no_package() {
+        let directory = Path::new(".");
+
+        assert_eq!(
+            cargo::Cargo.package_name(directory, "[package]\nname = \"alpha\"\n"),
+            Some("alpha".into())
+        );
+        assert_eq!(
+            cargo::Cargo.package_name(directory, "[workspace]\nmembers = [\"crates/*\"]\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn maven_parent_artifact_is_skipped() {
+        let manifest = "<project>\n  <parent>\n    <artifactId>parent</artifactId>\n  </parent>\n  <artifactId>module</artifactId>\n</project>\n";
+
+        assert_eq!(
+            maven::Maven.package_name(Path::new("."), manifest),
+            Some("module".into())
+        );
+    }
+
+    #[test]
+    fn go_module_path_is_used() {
+        let manifest = "// comment\nmodule example.com/service\n\ngo 1.22\n";
+
+        assert_eq!(
+            go::Go.package_name(Path::new("."), manifest),
+            Some("example.com/service".into())
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use super::PackageDetector;
+
+/// `package.json` packages, which covers npm, yarn and pnpm workspace members.
+pub struct Npm;
+
+impl PackageDetector for Npm {
+    fn name(&self) -> &'static str {
+        "npm"
+    }
+
+    fn manifest_names(&self) -> &'static [&'static str] {
+        &["package.json"]
+    }
+
+    fn package_name(&self, _directory: &Path, manifest: &str) -> Option<String> {
+        let manifest: serde_json::Value = serde_json::from_str(manifest).ok()?;
+        let name = manifest.get("name")?.as_str()?;
+        Some(name.to_string())
+    }
+}
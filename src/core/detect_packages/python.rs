@@ -0,0 +1,26 @@
+use std::path::Path;
+
+use super::PackageDetector;
+
+/// `pyproject.toml` projects, declared through PEP 621 or Poetry.
+pub struct Python;
+
+impl PackageDetector for Python {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn manifest_names(&self) -> &'static [&'static str] {
+        &["pyproject.toml"]
+    }
+
+    fn package_name(&self, _directory: &Path, manifest: &str) -> Option<String> {
+        let manifest: toml::Table = manifest.parse().ok()?;
+        let name = manifest
+            .get("project")
+            .and_then(|project| project.get("name"))
+            .or_else(|| manifest.get("tool")?.get("poetry")?.get("name"))?
+            .as_str()?;
+        Some(name.to_string())
+    }
+}
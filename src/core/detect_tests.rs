@@ -0,0 +1,87 @@
+use regex::Regex;
+use std::path::Path;
+
+use crate::context::AppContext;
+
+pub fn is_test_path(path: &Path, app_context: &AppContext) -> bool {
+    // test paths are anchored at the scan root
+    let relative_path = path.strip_prefix(&app_context.path).unwrap_or(path);
+
+    app_context
+        .config
+        .compiled_test_paths
+        .is_match(relative_path)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum BlockState {
+    #[default]
+    Outside,
+    // marker seen, waiting for the opening brace of the item it annotates
+    Pending,
+    Inside(usize),
+}
+
+/// Tracks brace-delimited blocks introduced by a marker line such as `#[cfg(test)]`.
+#[derive(Debug, Default)]
+pub struct TestBlockTracker {
+    state: BlockState,
+}
+
+impl TestBlockTracker {
+    /// Advances the tracker by one line and returns whether that line belongs to a test block.
+    pub fn advance(&mut self, line: &str, markers: &[Regex]) -> bool {
+        if self.state == BlockState::Outside {
+            if !markers.iter().any(|marker| marker.is_match(line)) {
+                return false;
+            }
+            self.state = BlockState::Pending;
+        }
+
+        for c in line.chars() {
+            self.state = match (self.state, c) {
+                (BlockState::Pending, '{') => BlockState::Inside(1),
+                // the marker annotated an item without a body, e.g. `use foo;`
+                (BlockState::Pending, ';') => BlockState::Outside,
+                (BlockState::Inside(depth), '{') => BlockState::Inside(depth + 1),
+                (BlockState::Inside(1), '}') => BlockState::Outside,
+                (BlockState::Inside(depth), '}') => BlockState::Inside(depth - 1),
+                (state, _) => state,
+            };
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod detect_tests_tests {
+    use super::TestBlockTracker;
+    use regex::Regex;
+
+    fn test_lines(content: &str) -> Vec<bool> {
+        let markers = vec![Regex::new(r"^\s*#\[cfg\(test\)\]").unwrap()];
+        let mut tracker = TestBlockTracker::default();
+        content
+            .lines()
+            .map(|line| tracker.advance(line, &markers))
+            .collect()
+    }
+
+    #[test]
+    fn cfg_test_module_is_tracked() {
+        let content = "fn a() {}\n#[cfg(test)]\nmod tests {\n    fn b() {\n    }\n}\nfn c() {}\n";
+
+        assert_eq!(
+            test_lines(content),
+            vec![false, true, true, true, true, true, false]
+        );
+    }
+
+    #[test]
+    fn marker_without_body_ends_at_semicolon() {
+        let content = "#[cfg(test)]\nuse std::fs;\nfn a() {}\n";
+
+        assert_eq!(test_lines(content), vec![true, true, false]);
+    }
+}
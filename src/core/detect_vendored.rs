@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use crate::context::AppContext;
+
+pub fn is_vendored(path: &Path, app_context: &AppContext) -> bool {
+    // vendored paths are anchored at the scan root
+    let relative_path = path.strip_prefix(&app_context.path).unwrap_or(path);
+
+    app_context
+        .config
+        .compiled_vendored_paths
+        .is_match(relative_path)
+}
+
+#[cfg(test)]
+mod detect_vendored_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+    use globset::{GlobBuilder, GlobSetBuilder};
+
+    fn vendored(app_context: &AppContext, path: &str) -> bool {
+        is_vendored(&app_context.path.join(path), app_context)
+    }
+
+    #[test]
+    fn built_in_vendor_directories_are_vendored_at_any_depth() {
+        let repository = TestRepository::new();
+        let app_context = repository.app_context(&[]);
+
+        for path in [
+            "vendor/lib.js",
+            "web/vendored/lib.js",
+            "third_party/zlib/inflate.c",
+            "src/third-party/lib.rs",
+            "thirdparty/lib.go",
+            "external/lib.cpp",
+            "extern/lib.h",
+            "deps/lib.c",
+        ] {
+            assert!(vendored(&app_context, path), "{path} should be vendored");
+        }
+    }
+
+    #[test]
+    fn similar_names_are_not_vendored() {
+        let repository = TestRepository::new();
+        let app_context = repository.app_context(&[]);
+
+        for path in [
+            "vendor.rs",
+            "src/vendor.rs",
+            "vendors/lib.js",
+            "src/my_vendor/lib.js",
+            "dependencies/lib.c",
+            "external.go",
+        ] {
+            assert!(
+                !vendored(&app_context, path),
+                "{path} should not be vendored"
+            );
+        }
+    }
+
+    #[test]
+    fn configured_patterns_replace_the_built_in_ones() {
+        let repository = TestRepository::new();
+        let mut app_context = repository.app_context(&[]);
+        // compiled the same way as the vendored_paths setting of the config
+        let mut builder = GlobSetBuilder::new();
+        builder.add(
+            GlobBuilder::new("libs/*/src/**")
+                .literal_separator(true)
+                .build()
+                .unwrap(),
+        );
+        app_context.config.compiled_vendored_paths = builder.build().unwrap();
+
+        assert!(vendored(&app_context, "libs/zlib/src/inflate.c"));
+        assert!(!vendored(&app_context, "libs/zlib/inflate.c"));
+        assert!(!vendored(&app_context, "app/libs/zlib/src/inflate.c"));
+        assert!(!vendored(&app_context, "vendor/lib.js"));
+    }
+}
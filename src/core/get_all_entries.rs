@@ -6,66 +6,263 @@ use std::{
 };
 use walkdir::{DirEntry, WalkDir};
 
-use crate::context::AppContext;
+use crate::{
+    context::AppContext,
+    core::{
+        SourceFile,
+        git::{
+            GitError, get_changed_paths, get_excludes_file, get_revision_files, get_tracked_files,
+        },
+    },
+};
 
-pub fn get_all_entries(app_context: &AppContext) -> Vec<DirEntry> {
-    let tracked_extensions: HashSet<String> = app_context
-        .config
-        .tracked
-        .iter()
-        .flat_map(|t| t.extensions.iter().map(|e| e.to_lowercase()))
-        .collect();
+#[derive(Debug, Default)]
+struct GitignoreCache {
+    // per-directory ignore files, keyed by canonical directory
+    directories: HashMap<PathBuf, Gitignore>,
+    // repository wide excludes rooted at the repository root, lowest precedence first
+    repository: Vec<(PathBuf, Gitignore)>,
+}
 
-    let ignored_files: HashSet<String> = app_context.config.ignored_files.iter().cloned().collect();
+impl GitignoreCache {
+    fn is_empty(&self) -> bool {
+        self.directories.is_empty() && self.repository.is_empty()
+    }
+}
 
-    let ignored_directories: HashSet<String> = app_context
-        .config
-        .ignored_directories
-        .iter()
-        .cloned()
-        .collect();
+// which files a listing keeps, on top of the ignore rules
+enum Selection<'a> {
+    TrackedExtensions(HashSet<String>),
+    FileNames(&'a [&'a str]),
+}
+
+impl Selection<'_> {
+    fn tracked_extensions(app_context: &AppContext) -> Self {
+        Selection::TrackedExtensions(
+            app_context
+                .config
+                .tracked
+                .iter()
+                .flat_map(|t| t.extensions.iter().map(|e| e.to_lowercase()))
+                .collect(),
+        )
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            Selection::TrackedExtensions(extensions) => path
+                .extension()
+                .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+                .unwrap_or(false),
+            Selection::FileNames(names) => path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| names.contains(&name)),
+        }
+    }
+}
+
+pub fn get_all_entries(app_context: &AppContext) -> Result<Vec<SourceFile>, GitError> {
+    list_entries(
+        app_context,
+        &Selection::tracked_extensions(app_context),
+        app_context.changed_since.as_deref(),
+    )
+}
 
-    // canonicalize root once
-    let canonical_root = app_context
-        .path
-        .canonicalize()
-        .unwrap_or_else(|_| app_context.path.clone());
+/// Lists the files named like one of `file_names`, from the same source and with the same ignore
+/// rules as [`get_all_entries`]. A `--changed-since` base does not apply, so unchanged files are
+/// listed as well.
+pub(super) fn get_named_entries(
+    app_context: &AppContext,
+    file_names: &[&str],
+) -> Result<Vec<SourceFile>, GitError> {
+    list_entries(app_context, &Selection::FileNames(file_names), None)
+}
+
+fn list_entries(
+    app_context: &AppContext,
+    selection: &Selection,
+    changed_since: Option<&str>,
+) -> Result<Vec<SourceFile>, GitError> {
+    if let Some(revision) = &app_context.revision {
+        let files = get_revision_files(app_context, &app_context.canonical_root(), revision)?;
+        return Ok(filter_listed_files(app_context, files, selection));
+    }
+
+    let ignored_directories = &app_context.config.compiled_ignored_directories;
+    let canonical_root = app_context.canonical_root();
 
     // build gitignore cache first
-    let gitignore_cache = if app_context.config.respect_gitignore {
-        build_gitignore_cache(
+    let ignore_file_names = ignore_file_names(app_context);
+    let mut gitignore_cache = GitignoreCache::default();
+
+    if !ignore_file_names.is_empty() {
+        gitignore_cache.directories = build_gitignore_cache(
             &app_context.path,
             &canonical_root,
             app_context.config.ignore_dotfolders,
-            &ignored_directories,
+            ignored_directories,
+            &ignore_file_names,
+        );
+    }
+
+    if app_context.config.respect_gitignore {
+        gitignore_cache.repository = build_repository_excludes(app_context, &canonical_root);
+    }
+
+    // collect all files (sequential), either from disk or from the git index
+    let all_files: Vec<SourceFile> = if app_context.git_tracked {
+        exclude_ignored_directories(
+            get_tracked_files(app_context, &canonical_root)?,
+            app_context,
         )
     } else {
-        HashMap::new()
+        collect_directory_entries(
+            &app_context.path,
+            app_context.config.ignore_dotfolders,
+            ignored_directories,
+        )
+        .into_iter()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| SourceFile::on_disk(entry.into_path()))
+        .collect()
     };
 
-    // collect all entries (sequential)
-    let all_entries = collect_directory_entries(
-        &app_context.path,
-        app_context.config.ignore_dotfolders,
-        &ignored_directories,
-    );
+    // restrict to files that changed relative to a base revision
+    let all_files = match changed_since {
+        Some(base) => {
+            let changed_paths = get_changed_paths(app_context, &canonical_root, base)?;
+            all_files
+                .into_iter()
+                .filter(|file| changed_paths.contains(&file.path))
+                .collect()
+        }
+        None => all_files,
+    };
 
-    // filter entries in parallel
-    filter_entries_parallel(
-        all_entries,
+    // filter files in parallel
+    Ok(filter_files_parallel(
+        all_files,
         app_context,
         &canonical_root,
-        &tracked_extensions,
-        &ignored_files,
+        selection,
         &gitignore_cache,
+    ))
+}
+
+/// Lists the files of a git revision. A revision is read from the object database only, so
+/// ignore files on disk do not apply, but every other filter does.
+pub fn get_revision_entries(
+    app_context: &AppContext,
+    revision: &str,
+) -> Result<Vec<SourceFile>, GitError> {
+    let canonical_root = app_context.canonical_root();
+    let files = get_revision_files(app_context, &canonical_root, revision)?;
+
+    Ok(filter_revision_entries(app_context, files))
+}
+
+/// Applies the filters of [`get_revision_entries`] to blobs that were found some other way,
+/// such as the changed files of a diff.
+pub fn filter_revision_entries(
+    app_context: &AppContext,
+    files: Vec<SourceFile>,
+) -> Vec<SourceFile> {
+    filter_listed_files(
+        app_context,
+        files,
+        &Selection::tracked_extensions(app_context),
+    )
+}
+
+// the filters for files that were not found by walking the directory
+fn filter_listed_files(
+    app_context: &AppContext,
+    files: Vec<SourceFile>,
+    selection: &Selection,
+) -> Vec<SourceFile> {
+    filter_files_parallel(
+        exclude_ignored_directories(files, app_context),
+        app_context,
+        &app_context.canonical_root(),
+        selection,
+        &GitignoreCache::default(),
     )
 }
 
+// per-directory ignore files, rules from later files take precedence over earlier ones
+fn ignore_file_names(app_context: &AppContext) -> Vec<&'static str> {
+    let mut names = Vec::new();
+
+    if app_context.config.respect_gitignore {
+        names.push(".gitignore");
+
+        if app_context.config.respect_ignore_files {
+            names.push(".ignore");
+        }
+    }
+
+    if app_context.config.respect_compositionignore {
+        names.push(".compositionignore");
+    }
+
+    names
+}
+
+pub(super) fn find_repository_root(canonical_root: &Path) -> Option<&Path> {
+    canonical_root
+        .ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+}
+
+// excludes that git applies to the whole repository, rather than to a single directory
+fn build_repository_excludes(
+    app_context: &AppContext,
+    canonical_root: &Path,
+) -> Vec<(PathBuf, Gitignore)> {
+    let mut excludes = Vec::new();
+
+    let Some(repository_root) = find_repository_root(canonical_root) else {
+        return excludes;
+    };
+
+    // core.excludesFile, defaulting to $XDG_CONFIG_HOME/git/ignore
+    if app_context.config.respect_global_gitignore
+        && let Some(excludes_path) = get_excludes_file(canonical_root)
+        && excludes_path.exists()
+    {
+        let mut builder = GitignoreBuilder::new(repository_root);
+        builder.add(excludes_path);
+        if let Ok(gitignore) = builder.build()
+            && !gitignore.is_empty()
+        {
+            excludes.push((repository_root.to_path_buf(), gitignore));
+        }
+    }
+
+    if app_context.config.respect_git_exclude {
+        let mut builder = GitignoreBuilder::new(repository_root);
+        let exclude_path = repository_root.join(".git").join("info").join("exclude");
+        if exclude_path.exists() {
+            builder.add(exclude_path);
+        }
+
+        if let Ok(gitignore) = builder.build()
+            && !gitignore.is_empty()
+        {
+            excludes.push((repository_root.to_path_buf(), gitignore));
+        }
+    }
+
+    excludes
+}
+
 fn should_traverse_directory(
     entry: &DirEntry,
     root: &Path,
     ignore_dotfolders: bool,
-    ignored_directories: &HashSet<String>,
+    ignored_directories: &Gitignore,
 ) -> bool {
     // check directory ignore settings
     if !entry.file_type().is_dir() {
@@ -76,23 +273,65 @@ fn should_traverse_directory(
         return true;
     }
 
-    let file_name = entry.file_name().to_string_lossy();
+    let relative_path = entry.path().strip_prefix(root).unwrap_or(entry.path());
+    !is_ignored_directory(relative_path, ignore_dotfolders, ignored_directories)
+}
 
-    if ignore_dotfolders && file_name.starts_with('.') {
-        return false;
-    }
+fn is_ignored_directory(
+    relative_path: &Path,
+    ignore_dotfolders: bool,
+    ignored_directories: &Gitignore,
+) -> bool {
+    let is_dotfolder = relative_path
+        .file_name()
+        .map(|name| name.to_string_lossy().starts_with('.'))
+        .unwrap_or(false);
 
-    if ignored_directories.contains(file_name.as_ref()) {
-        return false;
+    if ignore_dotfolders && is_dotfolder {
+        return true;
     }
 
-    true
+    // ignored directories are gitignore-style globs anchored at the scan root
+    ignored_directories.matched(relative_path, true).is_ignore()
+}
+
+// applies the traversal rules to files that were not found by walking the directory
+fn exclude_ignored_directories(
+    files: Vec<SourceFile>,
+    app_context: &AppContext,
+) -> Vec<SourceFile> {
+    files
+        .into_iter()
+        .filter(|file| {
+            !is_in_ignored_directory(
+                &file.path,
+                &app_context.path,
+                app_context.config.ignore_dotfolders,
+                &app_context.config.compiled_ignored_directories,
+            )
+        })
+        .collect()
+}
+
+fn is_in_ignored_directory(
+    path: &Path,
+    root: &Path,
+    ignore_dotfolders: bool,
+    ignored_directories: &Gitignore,
+) -> bool {
+    let relative_path = path.strip_prefix(root).unwrap_or(path);
+
+    relative_path
+        .ancestors()
+        .skip(1)
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .any(|ancestor| is_ignored_directory(ancestor, ignore_dotfolders, ignored_directories))
 }
 
 fn collect_directory_entries(
     root: &Path,
     ignore_dotfolders: bool,
-    ignored_directories: &HashSet<String>,
+    ignored_directories: &Gitignore,
 ) -> Vec<DirEntry> {
     WalkDir::new(root)
         .follow_links(false)
@@ -104,26 +343,23 @@ fn collect_directory_entries(
         .collect()
 }
 
-fn filter_entries_parallel(
-    entries: Vec<DirEntry>,
+fn filter_files_parallel(
+    files: Vec<SourceFile>,
     app_context: &AppContext,
     canonical_root: &Path,
-    tracked_extensions: &HashSet<String>,
-    ignored_files: &HashSet<String>,
-    gitignore_cache: &HashMap<PathBuf, Gitignore>,
-) -> Vec<DirEntry> {
-    entries
+    selection: &Selection,
+    gitignore_cache: &GitignoreCache,
+) -> Vec<SourceFile> {
+    files
         .into_par_iter()
-        .filter(|entry| {
-            entry.file_type().is_file()
-                && filter_file(
-                    entry,
-                    app_context,
-                    canonical_root,
-                    tracked_extensions,
-                    ignored_files,
-                    gitignore_cache,
-                )
+        .filter(|file| {
+            filter_file(
+                &file.path,
+                app_context,
+                canonical_root,
+                selection,
+                gitignore_cache,
+            )
         })
         .collect()
 }
@@ -132,12 +368,13 @@ fn build_gitignore_cache(
     root: &Path,
     canonical_root: &Path,
     ignore_dotfolders: bool,
-    ignored_directories: &HashSet<String>,
+    ignored_directories: &Gitignore,
+    ignore_file_names: &[&str],
 ) -> HashMap<PathBuf, Gitignore> {
     let mut cache = HashMap::new();
 
     // root gitignore
-    if let Ok(gitignore) = build_gitignore(root) {
+    if let Ok(gitignore) = build_gitignore(root, ignore_file_names) {
         cache.insert(canonical_root.to_path_buf(), gitignore);
     }
 
@@ -148,10 +385,10 @@ fn build_gitignore_cache(
 
     for dir_entry in directories {
         // infer canonical path by appending relative path to canonical root
-        if let Some(canonical_dir) = infer_canonical_path(dir_entry.path(), root, canonical_root) {
-            if let Ok(gitignore) = build_gitignore(dir_entry.path()) {
-                cache.insert(canonical_dir, gitignore);
-            }
+        if let Some(canonical_dir) = infer_canonical_path(dir_entry.path(), root, canonical_root)
+            && let Ok(gitignore) = build_gitignore(dir_entry.path(), ignore_file_names)
+        {
+            cache.insert(canonical_dir, gitignore);
         }
     }
 
@@ -165,58 +402,66 @@ fn infer_canonical_path(path: &Path, root: &Path, canonical_root: &Path) -> Opti
 }
 
 fn filter_file(
-    entry: &DirEntry,
+    path: &Path,
     app_context: &AppContext,
     canonical_root: &Path,
-    tracked_extensions: &HashSet<String>,
-    ignored_files: &HashSet<String>,
-    gitignore_cache: &HashMap<PathBuf, Gitignore>,
+    selection: &Selection,
+    gitignore_cache: &GitignoreCache,
 ) -> bool {
-    let file_name = entry.file_name().to_string_lossy();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
 
     if app_context.config.ignore_dotfiles && file_name.starts_with('.') {
         return false;
     }
 
-    if ignored_files.contains(file_name.as_ref()) {
+    // ignored files are gitignore-style globs anchored at the scan root
+    let relative_path = path.strip_prefix(&app_context.path).unwrap_or(path);
+    if app_context
+        .config
+        .compiled_ignored_files
+        .matched(relative_path, false)
+        .is_ignore()
+    {
         return false;
     }
 
-    // check extension
-    if !has_tracked_extension(entry.path(), tracked_extensions) {
+    // check extension, or file name
+    if !selection.matches(path) {
         return false;
     }
 
-    // check gitignore
-    if app_context.config.respect_gitignore {
-        if is_ignored_by_git(entry, &app_context.path, canonical_root, gitignore_cache) {
-            return false;
-        }
+    // check gitignore and other per-directory ignore files
+    if !gitignore_cache.is_empty()
+        && is_ignored_by_git(
+            path,
+            false,
+            &app_context.path,
+            canonical_root,
+            gitignore_cache,
+        )
+    {
+        return false;
     }
 
     true
 }
 
-fn has_tracked_extension(path: &Path, tracked_extensions: &HashSet<String>) -> bool {
-    path.extension()
-        .map(|ext| tracked_extensions.contains(&ext.to_string_lossy().to_lowercase()))
-        .unwrap_or(false)
-}
-
 fn is_ignored_by_git(
-    entry: &DirEntry,
+    path: &Path,
+    is_dir: bool,
     root: &Path,
     canonical_root: &Path,
-    gitignore_cache: &HashMap<PathBuf, Gitignore>,
+    gitignore_cache: &GitignoreCache,
 ) -> bool {
     // infer canonical_path
-    let canonical_path = match infer_canonical_path(entry.path(), root, canonical_root) {
+    let canonical_path = match infer_canonical_path(path, root, canonical_root) {
         Some(path) => path,
         None => return false,
     };
 
-    let is_dir = entry.file_type().is_dir();
-
     // for files, check gitignores up to and including the parent directory
     // for directories, check up to the directory itself
     let check_path = if is_dir {
@@ -232,7 +477,7 @@ fn is_ignored_by_git(
 fn build_gitignore_stack<'a>(
     check_path: &'a Path,
     canonical_root: &Path,
-    gitignore_cache: &'a HashMap<PathBuf, Gitignore>,
+    gitignore_cache: &'a GitignoreCache,
 ) -> Vec<(&'a Path, &'a Gitignore)> {
     let mut stack = Vec::new();
 
@@ -242,11 +487,16 @@ fn build_gitignore_stack<'a>(
             break;
         }
 
-        if let Some(gitignore) = gitignore_cache.get(ancestor) {
-            stack.push((ancestor.as_ref(), gitignore));
+        if let Some(gitignore) = gitignore_cache.directories.get(ancestor) {
+            stack.push((ancestor, gitignore));
         }
     }
 
+    // repository wide excludes have the lowest precedence
+    for (repository_root, gitignore) in gitignore_cache.repository.iter().rev() {
+        stack.push((repository_root.as_path(), gitignore));
+    }
+
     // reverse to check from parent to child
     stack.reverse();
     stack
@@ -278,13 +528,139 @@ fn apply_gitignore_rules(
     final_decision.unwrap_or(false)
 }
 
-fn build_gitignore(dir: &Path) -> Result<Gitignore, ignore::Error> {
+fn build_gitignore(dir: &Path, ignore_file_names: &[&str]) -> Result<Gitignore, ignore::Error> {
     let mut builder = GitignoreBuilder::new(dir);
-    let gitignore_path = dir.join(".gitignore");
 
-    if gitignore_path.exists() {
-        builder.add(gitignore_path);
+    for name in ignore_file_names {
+        let ignore_file_path = dir.join(name);
+        if ignore_file_path.exists() {
+            builder.add(ignore_file_path);
+        }
     }
 
     builder.build()
 }
+
+#[cfg(test)]
+mod get_all_entries_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    // the listed files relative to the repository root, sorted
+    fn listed(app_context: &AppContext) -> Vec<String> {
+        let mut paths: Vec<String> = get_all_entries(app_context)
+            .unwrap()
+            .into_iter()
+            .map(|file| {
+                let path = file.path.strip_prefix(&app_context.path).unwrap();
+                path.to_string_lossy().replace('\\', "/")
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    // compiled the same way as the ignored directory and file settings of the config
+    fn ignore_globs(patterns: &[&str]) -> Gitignore {
+        let mut builder = GitignoreBuilder::new(".");
+        for pattern in patterns {
+            builder.add_line(None, pattern).unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn anchored_ignore_globs_only_match_at_the_scan_root() {
+        let repository = TestRepository::new();
+        for path in [
+            "gen/a.rs",
+            "src/gen/b.rs",
+            "build/c.rs",
+            "src/build/d.rs",
+            "main.rs",
+            "src/main.rs",
+            "helper.rs",
+            "src/helper.rs",
+        ] {
+            repository.write(path, "fn f() {}\n");
+        }
+
+        let mut app_context = repository.app_context(&[]);
+        app_context.config.compiled_ignored_directories = ignore_globs(&["/gen", "build"]);
+        app_context.config.compiled_ignored_files = ignore_globs(&["/main.rs", "helper.rs"]);
+
+        assert_eq!(listed(&app_context), ["src/gen/b.rs", "src/main.rs"]);
+    }
+
+    #[test]
+    fn compositionignore_negates_and_overrides_gitignore() {
+        let repository = TestRepository::new();
+        repository.write(".gitignore", "*.gen.rs\n");
+        repository.write(".compositionignore", "!kept.gen.rs\nscratch.rs\n");
+        for path in ["main.rs", "dropped.gen.rs", "kept.gen.rs", "scratch.rs"] {
+            repository.write(path, "fn f() {}\n");
+        }
+
+        assert_eq!(
+            listed(&repository.app_context(&[])),
+            ["kept.gen.rs", "main.rs"]
+        );
+        assert_eq!(
+            listed(&repository.app_context(&["--respect-compositionignore=false"])),
+            ["main.rs", "scratch.rs"]
+        );
+    }
+
+    #[test]
+    fn git_excludes_apply_unless_turned_off() {
+        let repository = TestRepository::new();
+        repository.write(".git/info/exclude", "local.rs\n");
+        repository.write("excludes", "global.rs\n");
+        let excludes_path = repository.root.join("excludes");
+        repository
+            .repository
+            .config()
+            .unwrap()
+            .set_str("core.excludesFile", &excludes_path.to_string_lossy())
+            .unwrap();
+        for path in ["main.rs", "local.rs", "global.rs"] {
+            repository.write(path, "fn f() {}\n");
+        }
+
+        assert_eq!(listed(&repository.app_context(&[])), ["main.rs"]);
+        assert_eq!(
+            listed(&repository.app_context(&["--respect-git-exclude=false"])),
+            ["local.rs", "main.rs"]
+        );
+        assert_eq!(
+            listed(&repository.app_context(&["--respect-global-gitignore=false"])),
+            ["global.rs", "main.rs"]
+        );
+    }
+
+    #[test]
+    fn git_tracked_files_go_through_the_other_filters() {
+        let repository = TestRepository::new();
+        repository.write(".compositionignore", "skipped.rs\n");
+        for path in [
+            "main.rs",
+            "skipped.rs",
+            "build/out.rs",
+            ".cache/cached.rs",
+            "notes.txt",
+        ] {
+            repository.write(path, "fn f() {}\n");
+        }
+        repository.commit("Alice", "2024-01-10");
+        repository.write("untracked.rs", "fn f() {}\n");
+
+        assert_eq!(
+            listed(&repository.app_context(&["--git-tracked"])),
+            ["main.rs"]
+        );
+        assert_eq!(
+            listed(&repository.app_context(&["--git-tracked", "--ignore-dotfolders=false"])),
+            [".cache/cached.rs", "main.rs"]
+        );
+    }
+}
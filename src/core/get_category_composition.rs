@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use crate::{context::config::Category, core::CompositionEntry};
+
+#[derive(Debug)]
+pub struct CategoryComposition {
+    pub category: Category,
+    pub entries: Vec<CompositionEntry>,
+}
+
+impl CategoryComposition {
+    pub fn line_count(&self) -> usize {
+        self.entries.iter().map(|e| e.line_count).sum()
+    }
+}
+
+/// Rolls the per-language entries up into the category of each tracked language.
+pub fn get_category_composition(
+    composition_entries: &[CompositionEntry],
+) -> Vec<CategoryComposition> {
+    let mut entries_by_category: BTreeMap<Category, Vec<CompositionEntry>> = BTreeMap::new();
+    for entry in composition_entries.iter().filter(|e| e.line_count > 0) {
+        entries_by_category
+            .entry(entry.tracked.category())
+            .or_default()
+            .push(entry.clone());
+    }
+
+    entries_by_category
+        .into_iter()
+        .map(|(category, entries)| CategoryComposition { category, entries })
+        .collect()
+}
+
+#[cfg(test)]
+mod get_category_composition_tests {
+    use super::*;
+    use crate::core::{get_all_entries, get_composition, git::test_repository::TestRepository};
+
+    #[test]
+    fn languages_roll_up_into_their_category() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\nfn two() {}\n");
+        repository.write("main.py", "one = 1\n");
+        repository.write("README.md", "Some prose.\nMore prose.\n");
+        repository.write("data.json", "\"one\": 1\n");
+        let app_context = repository.app_context(&["--by-category"]);
+        let files = get_all_entries(&app_context).unwrap();
+        let composition = get_composition(&app_context, files);
+
+        let categories: Vec<(Category, usize, Vec<String>)> =
+            get_category_composition(&composition.entries)
+                .into_iter()
+                .map(|category| {
+                    let mut languages: Vec<String> = category
+                        .entries
+                        .iter()
+                        .map(|e| e.tracked.display.clone())
+                        .collect();
+                    languages.sort();
+                    (category.category, category.line_count(), languages)
+                })
+                .collect();
+
+        assert_eq!(
+            categories,
+            [
+                (
+                    Category::Programming,
+                    3,
+                    vec!["Python".to_string(), "Rust".to_string()]
+                ),
+                (Category::Data, 1, vec!["JSON".to_string()]),
+                (Category::Prose, 2, vec!["Markdown".to_string()]),
+            ]
+        );
+    }
+}
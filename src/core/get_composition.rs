@@ -1,107 +1,767 @@
+use git2::Oid;
 use rayon::prelude::*;
-use std::{collections::HashMap, fs};
-use walkdir::DirEntry;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    ops::AddAssign,
+    path::{Path, PathBuf},
+};
 
-use crate::context::{AppContext, config::Tracked};
+use crate::{
+    context::{
+        AppContext,
+        config::{Config, Tracked},
+    },
+    core::{
+        ContentReader, ReadError, SourceFile,
+        classify_file::{FileClass, classify_file},
+        detect_generated::is_generated,
+        detect_license::detect_license,
+        detect_tests::{TestBlockTracker, is_test_path},
+        detect_vendored::is_vendored,
+    },
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CompositionEntry {
     pub tracked: Tracked,
     pub line_count: usize,
+    pub test_line_count: usize,
+    pub generated_line_count: usize,
+    pub vendored_line_count: usize,
+    /// Every line of the hand-written files, before any filtering.
+    pub raw_line_count: usize,
+    /// Non-blank lines of the hand-written files that match none of the comment patterns.
+    pub code_line_count: usize,
+    /// Lines of the hand-written files that match a comment pattern.
+    pub comment_line_count: usize,
+    pub byte_count: usize,
+    pub file_count: usize,
+    /// Hand-written lines per file extension of the language, largest first.
+    pub extension_line_counts: Vec<(String, usize)>,
     pub percentage: f32,
 }
 
-pub fn get_composition(app_context: &AppContext, entries: Vec<DirEntry>) -> Vec<CompositionEntry> {
+impl CompositionEntry {
+    pub fn is_empty(&self) -> bool {
+        self.line_count == 0 && self.generated_line_count == 0 && self.vendored_line_count == 0
+    }
+
+    /// Adds the line counts of another entry of the same language.
+    pub fn merge(&mut self, other: &CompositionEntry) {
+        self.line_count += other.line_count;
+        self.test_line_count += other.test_line_count;
+        self.generated_line_count += other.generated_line_count;
+        self.vendored_line_count += other.vendored_line_count;
+        self.raw_line_count += other.raw_line_count;
+        self.code_line_count += other.code_line_count;
+        self.comment_line_count += other.comment_line_count;
+        self.byte_count += other.byte_count;
+        self.file_count += other.file_count;
+        for (ext, lines) in &other.extension_line_counts {
+            add_extension_lines(&mut self.extension_line_counts, ext, *lines);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Composition {
+    pub entries: Vec<CompositionEntry>,
+    pub skipped: Vec<SkippedFile>,
+    /// Per-directory entries, only filled when `app_context.directory_depth` is set.
+    pub directories: Vec<DirectoryComposition>,
+    /// Per-file counts, only filled when `app_context.keeps_file_line_counts()` is true.
+    pub files: Vec<FileLineCount>,
+}
+
+#[derive(Debug)]
+pub struct DirectoryComposition {
+    /// Directory relative to the scan root, empty for files directly in it.
+    pub path: PathBuf,
+    pub entries: Vec<CompositionEntry>,
+}
+
+/// Counted lines of a single file. `line_count` holds the hand-written lines, while generated
+/// and vendored files only count towards their own bucket.
+#[derive(Debug)]
+pub struct FileLineCount {
+    pub path: PathBuf,
+    pub tracked: Tracked,
+    pub line_count: usize,
+    pub generated_line_count: usize,
+    pub vendored_line_count: usize,
+    /// Only detected when `app_context.by_license` is set.
+    pub license: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SkipReason {
+    Binary,
+    Minified,
+    LfsPointer,
+    Generated,
+    NotUtf8,
+    PermissionDenied,
+    NotFound,
+    Io,
+    RepositoryUnavailable,
+}
+
+impl From<ReadError> for SkipReason {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Io(error) => match error.kind() {
+                io::ErrorKind::InvalidData => SkipReason::NotUtf8,
+                io::ErrorKind::PermissionDenied => SkipReason::PermissionDenied,
+                io::ErrorKind::NotFound => SkipReason::NotFound,
+                _ => SkipReason::Io,
+            },
+            ReadError::RepositoryUnavailable => SkipReason::RepositoryUnavailable,
+        }
+    }
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binary"),
+            SkipReason::Minified => write!(f, "minified"),
+            SkipReason::LfsPointer => write!(f, "Git LFS pointer"),
+            SkipReason::Generated => write!(f, "generated"),
+            SkipReason::NotUtf8 => write!(f, "non-UTF-8"),
+            SkipReason::PermissionDenied => write!(f, "permission denied"),
+            SkipReason::NotFound => write!(f, "not found"),
+            SkipReason::Io => write!(f, "I/O error"),
+            SkipReason::RepositoryUnavailable => write!(f, "repository could not be opened"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct LineCounts {
+    hand_written: usize,
+    test: usize,
+    generated: usize,
+    vendored: usize,
+    // unfiltered measures of the hand-written files
+    raw: usize,
+    code: usize,
+    comments: usize,
+    bytes: usize,
+    files: usize,
+}
+
+impl AddAssign for LineCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.hand_written += other.hand_written;
+        self.test += other.test;
+        self.generated += other.generated;
+        self.vendored += other.vendored;
+        self.raw += other.raw;
+        self.code += other.code;
+        self.comments += other.comments;
+        self.bytes += other.bytes;
+        self.files += other.files;
+    }
+}
+
+// what counting a single file yields
+#[derive(Debug, Default, Clone)]
+struct FileCounts {
+    lines: LineCounts,
+    // only detected when `app_context.by_license` is set
+    license: Option<String>,
+}
+
+type CountResult = Result<FileCounts, SkipReason>;
+
+/// Results of blobs that were already counted, keyed by blob id and path. Counting several
+/// revisions of the same tree with one cache only reads the blobs that changed in between.
+#[derive(Default)]
+pub struct CountCache {
+    results: HashMap<(Oid, PathBuf), CountResult>,
+}
+
+impl CountCache {
+    fn get(&self, file: &SourceFile) -> Option<CountResult> {
+        let blob_id = file.blob_id?;
+        self.results.get(&(blob_id, file.path.clone())).cloned()
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    line_counts_by_extension: HashMap<String, LineCounts>,
+    line_counts_by_directory: HashMap<PathBuf, HashMap<String, LineCounts>>,
+    files: Vec<FileLineCount>,
+    skipped: Vec<SkippedFile>,
+    // blob results that were not in the cache yet
+    counted: Vec<((Oid, PathBuf), CountResult)>,
+}
+
+impl Tally {
+    fn merge(mut self, other: Tally) -> Self {
+        for (ext, lines) in other.line_counts_by_extension {
+            *self.line_counts_by_extension.entry(ext).or_default() += lines;
+        }
+        for (directory, line_counts_by_extension) in other.line_counts_by_directory {
+            let merged = self.line_counts_by_directory.entry(directory).or_default();
+            for (ext, lines) in line_counts_by_extension {
+                *merged.entry(ext).or_default() += lines;
+            }
+        }
+        self.files.extend(other.files);
+        self.skipped.extend(other.skipped);
+        self.counted.extend(other.counted);
+        self
+    }
+}
+
+pub fn get_composition(app_context: &AppContext, files: Vec<SourceFile>) -> Composition {
+    get_composition_cached(app_context, files, &mut CountCache::default())
+}
+
+/// Like [`get_composition`], but reuses and fills `cache` for files that are stored as blobs.
+pub fn get_composition_cached(
+    app_context: &AppContext,
+    files: Vec<SourceFile>,
+    cache: &mut CountCache,
+) -> Composition {
     // process files in parallel
-    let line_counts_by_extension: HashMap<String, usize> = entries
+    let cached: &CountCache = cache;
+    let reader = ContentReader::new(&app_context.path);
+    let tally: Tally = files
         .par_iter()
-        .filter_map(|entry| {
-            let ext = entry.path().extension()?.to_str()?.to_lowercase();
-            let lines = count_lines(entry.path(), app_context, &ext)?;
-            Some((ext, lines))
+        .map(|file| {
+            let ext = file.path.extension()?.to_str()?.to_lowercase();
+            let (counts, is_fresh) = match cached.get(file) {
+                Some(counts) => (counts, false),
+                None => (count_lines(file, &reader, app_context, &ext), true),
+            };
+            Some((file, ext, counts, is_fresh))
         })
+        .flatten()
         .fold(
-            || HashMap::new(),
-            |mut map, (ext, lines)| {
-                *map.entry(ext).or_insert(0) += lines;
-                map
-            },
-        )
-        .reduce(
-            || HashMap::new(),
-            |mut map1, map2| {
-                for (ext, lines) in map2 {
-                    *map1.entry(ext).or_insert(0) += lines;
+            Tally::default,
+            |mut tally, (file, ext, counts, is_fresh)| {
+                if is_fresh && let Some(blob_id) = file.blob_id {
+                    tally
+                        .counted
+                        .push(((blob_id, file.path.clone()), counts.clone()));
                 }
-                map1
+
+                match counts {
+                    Ok(FileCounts { lines, license }) => {
+                        if let Some(depth) = app_context.directory_depth {
+                            *tally
+                                .line_counts_by_directory
+                                .entry(directory_at_depth(app_context, &file.path, depth))
+                                .or_default()
+                                .entry(ext.clone())
+                                .or_default() += lines;
+                        }
+                        if app_context.keeps_file_line_counts()
+                            && lines.hand_written + lines.generated + lines.vendored > 0
+                            && let Some(tracked) = find_tracked(app_context, &ext)
+                        {
+                            tally.files.push(FileLineCount {
+                                path: file.path.clone(),
+                                tracked: tracked.clone(),
+                                line_count: lines.hand_written,
+                                generated_line_count: lines.generated,
+                                vendored_line_count: lines.vendored,
+                                license,
+                            });
+                        }
+                        *tally.line_counts_by_extension.entry(ext).or_default() += lines;
+                    }
+                    Err(reason) => tally.skipped.push(SkippedFile {
+                        path: file.path.clone(),
+                        reason,
+                    }),
+                }
+                tally
             },
-        );
+        )
+        .reduce(Tally::default, Tally::merge);
+
+    cache.results.extend(tally.counted);
 
-    let composition: Vec<CompositionEntry> = app_context
+    let mut skipped = tally.skipped;
+    skipped.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let entries = composition_entries(app_context, &tally.line_counts_by_extension);
+
+    let mut directories: Vec<DirectoryComposition> = tally
+        .line_counts_by_directory
+        .into_iter()
+        .map(|(path, line_counts_by_extension)| DirectoryComposition {
+            path,
+            entries: composition_entries(app_context, &line_counts_by_extension),
+        })
+        .filter(|directory| !directory.entries.is_empty())
+        .collect();
+    directories.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut files = tally.files;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Composition {
+        entries,
+        skipped,
+        directories,
+        files,
+    }
+}
+
+fn add_extension_lines(extension_line_counts: &mut Vec<(String, usize)>, ext: &str, lines: usize) {
+    match extension_line_counts.iter_mut().find(|(e, _)| e == ext) {
+        Some((_, total)) => *total += lines,
+        None => extension_line_counts.push((ext.to_string(), lines)),
+    }
+    extension_line_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+}
+
+// one entry per tracked language that has lines in `line_counts_by_extension`
+fn composition_entries(
+    app_context: &AppContext,
+    line_counts_by_extension: &HashMap<String, LineCounts>,
+) -> Vec<CompositionEntry> {
+    app_context
         .config
         .tracked
         .iter()
         .filter_map(|tracked| {
-            let total_lines: usize = tracked
+            let mut total_lines = LineCounts::default();
+            let mut extension_line_counts = Vec::new();
+            for (ext, lines) in tracked
                 .extensions
                 .iter()
-                .filter_map(|ext| line_counts_by_extension.get(ext))
-                .sum();
-
-            if total_lines == 0 {
-                return None;
+                .filter_map(|ext| Some((ext, line_counts_by_extension.get(ext)?)))
+            {
+                total_lines += *lines;
+                if lines.hand_written > 0 {
+                    extension_line_counts.push((ext.clone(), lines.hand_written));
+                }
             }
+            extension_line_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
-            Some(CompositionEntry {
-                tracked: Tracked {
-                    display: tracked.display.clone(),
-                    extensions: tracked.extensions.clone(),
-                    color: tracked.color.clone(),
-                    excluded_patterns: tracked.excluded_patterns.clone(),
-                    compiled_excluded_patterns: tracked.compiled_excluded_patterns.clone(),
-                },
-                line_count: total_lines,
+            let entry = CompositionEntry {
+                tracked: tracked.clone(),
+                line_count: total_lines.hand_written,
+                test_line_count: total_lines.test,
+                generated_line_count: total_lines.generated,
+                vendored_line_count: total_lines.vendored,
+                raw_line_count: total_lines.raw,
+                code_line_count: total_lines.code,
+                comment_line_count: total_lines.comments,
+                byte_count: total_lines.bytes,
+                file_count: total_lines.files,
+                extension_line_counts,
                 percentage: 0.0,
+            };
+
+            (!entry.is_empty()).then_some(entry)
+        })
+        .collect()
+}
+
+// the directory of `path` relative to the scan root, cut off after `depth` components
+fn directory_at_depth(app_context: &AppContext, path: &Path, depth: usize) -> PathBuf {
+    let relative_path = path.strip_prefix(&app_context.path).unwrap_or(path);
+    relative_path
+        .parent()
+        .map(|parent| parent.components().take(depth).collect())
+        .unwrap_or_default()
+}
+
+/// Counts each file on its own instead of folding the results per language. Files without
+/// hand-written lines, such as skipped, generated or vendored files, are left out.
+pub fn get_file_line_counts(
+    app_context: &AppContext,
+    files: Vec<SourceFile>,
+) -> Vec<FileLineCount> {
+    let reader = ContentReader::new(&app_context.path);
+    files
+        .par_iter()
+        .map(|file| {
+            let ext = file.path.extension()?.to_str()?.to_lowercase();
+            let tracked = find_tracked(app_context, &ext)?;
+            let counts = count_lines(file, &reader, app_context, &ext).ok()?;
+
+            (counts.lines.hand_written > 0).then(|| FileLineCount {
+                path: file.path.clone(),
+                tracked: tracked.clone(),
+                line_count: counts.lines.hand_written,
+                generated_line_count: 0,
+                vendored_line_count: 0,
+                license: counts.license,
             })
         })
-        .collect();
+        .flatten()
+        .collect()
+}
+
+/// Rolls per-file counts up into per-language entries under every key a file maps to.
+pub fn group_file_line_counts<'a, K: Ord>(
+    files: &'a [FileLineCount],
+    keys: impl Fn(&'a FileLineCount) -> Vec<K>,
+) -> BTreeMap<K, Vec<CompositionEntry>> {
+    let mut entries_by_key: BTreeMap<K, Vec<CompositionEntry>> = BTreeMap::new();
+    for file in files {
+        for key in keys(file) {
+            let entry = CompositionEntry {
+                tracked: file.tracked.clone(),
+                line_count: file.line_count,
+                test_line_count: 0,
+                generated_line_count: file.generated_line_count,
+                vendored_line_count: file.vendored_line_count,
+                raw_line_count: 0,
+                code_line_count: 0,
+                comment_line_count: 0,
+                byte_count: 0,
+                file_count: 0,
+                extension_line_counts: file
+                    .path
+                    .extension()
+                    .filter(|_| file.line_count > 0)
+                    .map(|ext| vec![(ext.to_string_lossy().to_lowercase(), file.line_count)])
+                    .unwrap_or_default(),
+                percentage: 0.0,
+            };
+
+            let entries = entries_by_key.entry(key).or_default();
+            match entries
+                .iter_mut()
+                .find(|e| e.tracked.display == file.tracked.display)
+            {
+                Some(existing) => existing.merge(&entry),
+                None => entries.push(entry),
+            }
+        }
+    }
 
-    composition
+    entries_by_key
 }
 
-fn count_lines(path: &std::path::Path, app_context: &AppContext, ext: &str) -> Option<usize> {
-    let content = fs::read_to_string(path).ok()?;
+fn count_lines(
+    file: &SourceFile,
+    reader: &ContentReader,
+    app_context: &AppContext,
+    ext: &str,
+) -> Result<FileCounts, SkipReason> {
+    let path = file.path.as_path();
+    let content = reader.read(file).map_err(SkipReason::from)?;
+
+    let config = &app_context.config;
+    if let Some(reason) = classified_skip_reason(&content, config) {
+        return Err(reason);
+    }
+
+    let content = String::from_utf8(content).map_err(|_| SkipReason::NotUtf8)?;
+
+    let generated = is_generated(path, &content, config);
+    if generated && config.ignore_generated_files {
+        return Err(SkipReason::Generated);
+    }
+
+    let tracked = match find_tracked(app_context, ext) {
+        Some(tracked) => tracked,
+        None => return Ok(FileCounts::default()),
+    };
+
+    let is_counted = |line: &str| is_counted_line(line, app_context, tracked);
+
+    // vendored and generated code keep their license, so it can be reported under it
+    let license = if app_context.by_license {
+        detect_license(&content, config, tracked)
+    } else {
+        None
+    };
+
+    // vendored code is reported apart from first-party code, generated or not
+    let mut lines = LineCounts::default();
+    if is_vendored(path, app_context) {
+        lines.vendored = content.lines().filter(|line| is_counted(line)).count();
+        return Ok(FileCounts { lines, license });
+    }
+
+    if generated {
+        lines.generated = content.lines().filter(|line| is_counted(line)).count();
+        return Ok(FileCounts { lines, license });
+    }
+
+    // hand-written first-party lines are further split into source and test
+    let is_test_file = is_test_path(path, app_context);
+    let mut test_blocks = TestBlockTracker::default();
+    lines.bytes = content.len();
+    lines.files = 1;
+    for line in content.lines() {
+        lines.raw += 1;
+        if is_comment_line(line, tracked) {
+            lines.comments += 1;
+        } else if !line.trim().is_empty() {
+            lines.code += 1;
+        }
+
+        // block tracking has to see every line, including the ones that are not counted
+        let in_test_block = test_blocks.advance(line, &tracked.compiled_test_block_patterns);
+
+        if !is_counted(line) {
+            continue;
+        }
+
+        lines.hand_written += 1;
+        if is_test_file || in_test_block {
+            lines.test += 1;
+        }
+    }
+
+    Ok(FileCounts { lines, license })
+}
 
-    let tracked = app_context
+pub fn find_tracked<'a>(app_context: &'a AppContext, ext: &str) -> Option<&'a Tracked> {
+    app_context
         .config
         .tracked
         .iter()
-        .find(|t| t.extensions.iter().any(|e| e == ext))?;
+        .find(|t| t.extensions.iter().any(|e| e == ext))
+}
 
-    let count = content
-        .lines()
-        .filter(|line| {
-            if app_context.config.ignore_empty_lines && line.trim().is_empty() {
-                return false;
-            }
+pub fn is_counted_line(line: &str, app_context: &AppContext, tracked: &Tracked) -> bool {
+    if app_context.config.ignore_empty_lines && line.trim().is_empty() {
+        return false;
+    }
 
-            // global excluded patterns
-            for pattern in &app_context.config.compiled_excluded_patterns {
-                if pattern.is_match(line) {
-                    return false;
-                }
-            }
+    // global excluded patterns
+    for pattern in &app_context.config.compiled_excluded_patterns {
+        if pattern.is_match(line) {
+            return false;
+        }
+    }
 
-            // language specific excluded patters
-            for pattern in &tracked.compiled_excluded_patterns {
-                if pattern.is_match(line) {
-                    return false;
-                }
-            }
+    // language specific excluded patters
+    for pattern in &tracked.compiled_excluded_patterns {
+        if pattern.is_match(line) {
+            return false;
+        }
+    }
 
-            true
-        })
-        .count();
+    true
+}
+
+fn is_comment_line(line: &str, tracked: &Tracked) -> bool {
+    tracked
+        .compiled_comment_patterns
+        .iter()
+        .any(|pattern| pattern.is_match(line))
+}
+
+// binary, minified and LFS pointer files are only skipped when their setting asks for it
+fn classified_skip_reason(content: &[u8], config: &Config) -> Option<SkipReason> {
+    match classify_file(content, config) {
+        FileClass::Binary if config.ignore_binary_files => Some(SkipReason::Binary),
+        FileClass::Minified if config.ignore_minified_files => Some(SkipReason::Minified),
+        FileClass::LfsPointer if config.ignore_lfs_pointers => Some(SkipReason::LfsPointer),
+        _ => None,
+    }
+}
+
+/// Returns whether a file would be counted as hand-written, first-party code.
+pub fn is_first_party(path: &Path, content: &[u8], app_context: &AppContext) -> bool {
+    if classified_skip_reason(content, &app_context.config).is_some() {
+        return false;
+    }
+
+    let Ok(content) = std::str::from_utf8(content) else {
+        return false;
+    };
+
+    !is_vendored(path, app_context) && !is_generated(path, content, &app_context.config)
+}
 
-    Some(count)
+#[cfg(test)]
+mod get_composition_tests {
+    use super::*;
+    use crate::core::{get_all_entries, git::test_repository::TestRepository};
+
+    fn composition(repository: &TestRepository, args: &[&str]) -> Composition {
+        let app_context = repository.app_context(args);
+        let files = get_all_entries(&app_context).unwrap();
+        get_composition(&app_context, files)
+    }
+
+    fn lines_of(entries: &[CompositionEntry], display: &str) -> usize {
+        entries
+            .iter()
+            .find(|e| e.tracked.display == display)
+            .map_or(0, |e| e.line_count)
+    }
+
+    #[test]
+    fn directories_are_cut_off_at_the_requested_depth() {
+        let repository = TestRepository::new();
+        let app_context = repository.app_context(&[]);
+        let path = app_context.path.join("src/core/git/mod.rs");
+
+        assert_eq!(directory_at_depth(&app_context, &path, 1), Path::new("src"));
+        assert_eq!(
+            directory_at_depth(&app_context, &path, 2),
+            Path::new("src/core")
+        );
+        assert_eq!(
+            directory_at_depth(&app_context, &path, 5),
+            Path::new("src/core/git")
+        );
+        assert_eq!(
+            directory_at_depth(&app_context, &app_context.path.join("main.rs"), 1),
+            Path::new("")
+        );
+    }
+
+    #[test]
+    fn lines_are_grouped_per_directory() {
+        let repository = TestRepository::new();
+        repository.write("build.py", "one = 1\n");
+        repository.write("src/main.rs", "fn one() {}\nfn two() {}\n");
+        repository.write("src/core/mod.rs", "fn three() {}\n");
+        repository.write("scripts/run.py", "two = 2\nthree = 3\n");
+
+        let composition = composition(&repository, &["--by-dir"]);
+
+        let directories: Vec<_> = composition
+            .directories
+            .iter()
+            .map(|d| d.path.as_path())
+            .collect();
+        assert_eq!(
+            directories,
+            [Path::new(""), Path::new("scripts"), Path::new("src")]
+        );
+        assert_eq!(lines_of(&composition.directories[0].entries, "Python"), 1);
+        assert_eq!(lines_of(&composition.directories[1].entries, "Python"), 2);
+        assert_eq!(lines_of(&composition.directories[2].entries, "Rust"), 3);
+
+        // the overall composition is unaffected by the grouping
+        assert_eq!(lines_of(&composition.entries, "Rust"), 3);
+        assert_eq!(lines_of(&composition.entries, "Python"), 3);
+    }
+
+    #[test]
+    fn directories_are_only_collected_when_requested() {
+        let repository = TestRepository::new();
+        repository.write("src/main.rs", "fn one() {}\n");
+
+        assert!(composition(&repository, &[]).directories.is_empty());
+        let composition = composition(&repository, &["--by-dir", "--depth=2"]);
+        assert_eq!(composition.directories[0].path, Path::new("src"));
+    }
+
+    fn entry<'a>(entries: &'a [CompositionEntry], display: &str) -> &'a CompositionEntry {
+        entries
+            .iter()
+            .find(|e| e.tracked.display == display)
+            .unwrap()
+    }
+
+    #[test]
+    fn lines_are_split_per_extension_largest_first() {
+        let repository = TestRepository::new();
+        repository.write("stubs.pyi", "def one(): ...\n");
+        repository.write("main.py", "one = 1\ntwo = 2\n");
+        repository.write("tool.py", "three = 3\n");
+
+        let composition = composition(&repository, &["--expand"]);
+
+        let python = entry(&composition.entries, "Python");
+        assert_eq!(python.line_count, 4);
+        assert_eq!(
+            python.extension_line_counts,
+            [("py".to_string(), 3), ("pyi".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn merged_entries_add_up_their_extensions() {
+        let repository = TestRepository::new();
+        repository.write("a/main.py", "one = 1\n");
+        repository.write("b/stubs.pyi", "def one(): ...\ndef two(): ...\n");
+
+        let composition = composition(&repository, &["--by-dir"]);
+        let mut python = entry(&composition.directories[0].entries, "Python").clone();
+        python.merge(entry(&composition.directories[1].entries, "Python"));
+
+        assert_eq!(python.line_count, 3);
+        assert_eq!(
+            python.extension_line_counts,
+            [("pyi".to_string(), 2), ("py".to_string(), 1)]
+        );
+    }
+
+    fn tracked(config: &Config, display: &str) -> Tracked {
+        config
+            .tracked
+            .iter()
+            .find(|t| t.display == display)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn comment_markers_depend_on_the_language() {
+        let config = Config::default();
+        let rust = tracked(&config, "Rust");
+        let c = tracked(&config, "C");
+        let markdown = tracked(&config, "Markdown");
+        let python = tracked(&config, "Python");
+        let php = tracked(&config, "PHP");
+
+        assert!(is_comment_line("// line comment", &rust));
+        assert!(is_comment_line("    /// doc comment", &rust));
+        assert!(is_comment_line("/* block", &rust));
+        assert!(is_comment_line("  * continued", &rust));
+        assert!(is_comment_line("  */", &rust));
+        assert!(!is_comment_line("#[derive(Debug)]", &rust));
+        assert!(!is_comment_line("    #![allow(dead_code)]", &rust));
+
+        assert!(!is_comment_line("#include <stdio.h>", &c));
+        assert!(!is_comment_line("    *ptr = value;", &c));
+        assert!(is_comment_line(" *", &c));
+
+        assert!(!is_comment_line("* a bullet", &markdown));
+        assert!(!is_comment_line("# A heading", &markdown));
+        assert!(is_comment_line("<!-- hidden -->", &markdown));
+
+        assert!(is_comment_line("# comment", &python));
+        assert!(!is_comment_line("value = 1  # trailing", &python));
+
+        assert!(is_comment_line("# comment", &php));
+        assert!(!is_comment_line("#[Attribute]", &php));
+    }
+
+    #[test]
+    fn code_and_comment_lines_are_counted_per_language() {
+        let repository = TestRepository::new();
+        repository.write(
+            "main.rs",
+            "//! crate docs\n#[derive(Debug)]\nstruct A;\n\n/*\n * block\n */\nfn f(p: &mut u8) {\n    *p = 1;\n}\n",
+        );
+        repository.write("README.md", "# Title\n\n* one\n* two\n<!-- note -->\n");
+
+        let composition = composition(&repository, &["--metric=code"]);
+
+        let rust = entry(&composition.entries, "Rust");
+        assert_eq!((rust.code_line_count, rust.comment_line_count), (5, 4));
+        let markdown = entry(&composition.entries, "Markdown");
+        assert_eq!(
+            (markdown.code_line_count, markdown.comment_line_count),
+            (3, 1)
+        );
+    }
 }
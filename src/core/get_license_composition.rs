@@ -0,0 +1,55 @@
+use crate::core::{CompositionEntry, FileLineCount, group_file_line_counts};
+
+#[derive(Debug)]
+pub struct LicenseComposition {
+    /// `None` for files without a detected license.
+    pub license: Option<String>,
+    pub entries: Vec<CompositionEntry>,
+}
+
+/// Groups the counted lines of every file by the license detected in its header.
+pub fn get_license_composition(files: &[FileLineCount]) -> Vec<LicenseComposition> {
+    group_file_line_counts(files, |file| vec![file.license.as_deref()])
+        .into_iter()
+        .map(|(license, entries)| LicenseComposition {
+            license: license.map(str::to_string),
+            entries,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod get_license_composition_tests {
+    use super::*;
+    use crate::core::{get_all_entries, get_composition, git::test_repository::TestRepository};
+
+    // each license with its hand-written and vendored lines
+    fn licenses(repository: &TestRepository) -> Vec<(Option<String>, usize, usize)> {
+        let app_context = repository.app_context(&["--by-license"]);
+        let files = get_all_entries(&app_context).unwrap();
+        let composition = get_composition(&app_context, files);
+        get_license_composition(&composition.files)
+            .into_iter()
+            .map(|license| {
+                let lines = license.entries.iter().map(|e| e.line_count).sum();
+                let vendored = license.entries.iter().map(|e| e.vendored_line_count).sum();
+                (license.license, lines, vendored)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vendored_files_are_reported_under_their_license() {
+        let repository = TestRepository::new();
+        repository.write("main.js", "console.log(1);\n");
+        repository.write(
+            "vendor/lib.js",
+            "// SPDX-License-Identifier: MIT\nexport const a = 1;\nexport const b = 2;\n",
+        );
+
+        assert_eq!(
+            licenses(&repository),
+            [(None, 1, 0), (Some("MIT".to_string()), 0, 2)]
+        );
+    }
+}
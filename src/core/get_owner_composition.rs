@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use crate::{
+    context::AppContext,
+    core::{
+        CompositionEntry, FileLineCount, codeowners::CodeOwners,
+        get_all_entries::find_repository_root, git::read_revision_file, group_file_line_counts,
+    },
+};
+
+#[derive(Debug)]
+pub struct OwnerComposition {
+    /// `None` for files without an owner.
+    pub owner: Option<String>,
+    pub entries: Vec<CompositionEntry>,
+}
+
+#[derive(Debug)]
+pub struct OwnersNotFound(pub PathBuf);
+
+impl std::error::Error for OwnersNotFound {}
+
+impl std::fmt::Display for OwnersNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "no CODEOWNERS file found in '{}', '.github/' or 'docs/'",
+            self.0.display()
+        )
+    }
+}
+
+/// Assigns the counted lines of every file to its owners according to `CODEOWNERS`. A file with
+/// several owners counts towards each of them. With `--rev`, `CODEOWNERS` is read from that
+/// revision too.
+pub fn get_owner_composition(
+    app_context: &AppContext,
+    files: &[FileLineCount],
+) -> Result<Vec<OwnerComposition>, OwnersNotFound> {
+    let canonical_root = app_context.canonical_root();
+    let repository_root = find_repository_root(&canonical_root)
+        .unwrap_or(&canonical_root)
+        .to_path_buf();
+    let codeowners = match &app_context.revision {
+        Some(revision) => CodeOwners::load_with(&repository_root, |location| {
+            let content = read_revision_file(&canonical_root, revision, location)?;
+            String::from_utf8(content).ok()
+        }),
+        None => CodeOwners::load(&repository_root),
+    }
+    .ok_or_else(|| OwnersNotFound(repository_root.clone()))?;
+
+    let entries_by_owner = group_file_line_counts(files, |file| {
+        // CODEOWNERS patterns are anchored at the repository root
+        let relative_path = file
+            .path
+            .strip_prefix(&app_context.path)
+            .unwrap_or(&file.path);
+        let repository_path = canonical_root.join(relative_path);
+        let repository_path = repository_path
+            .strip_prefix(&repository_root)
+            .unwrap_or(relative_path);
+
+        let owners = codeowners.owners(repository_path);
+        if owners.is_empty() {
+            vec![None]
+        } else {
+            owners.iter().map(|owner| Some(owner.as_str())).collect()
+        }
+    });
+
+    Ok(entries_by_owner
+        .into_iter()
+        .map(|(owner, entries)| OwnerComposition {
+            owner: owner.map(str::to_string),
+            entries,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod get_owner_composition_tests {
+    use super::*;
+    use crate::core::{get_all_entries, get_composition, git::test_repository::TestRepository};
+
+    fn owners(repository: &TestRepository, args: &[&str]) -> Vec<Option<String>> {
+        let app_context = repository.app_context(args);
+        let files = get_all_entries(&app_context).unwrap();
+        let composition = get_composition(&app_context, files);
+        get_owner_composition(&app_context, &composition.files)
+            .unwrap()
+            .into_iter()
+            .map(|owners| owners.owner)
+            .collect()
+    }
+
+    #[test]
+    fn codeowners_is_read_from_the_counted_revision() {
+        let repository = TestRepository::new();
+        repository.write(".github/CODEOWNERS", "* @acme/old\n");
+        repository.write("main.rs", "fn main() {}\n");
+        repository.commit("Alice", "2024-01-10");
+        repository.write(".github/CODEOWNERS", "* @acme/new\n");
+
+        assert_eq!(
+            owners(&repository, &["--by-owner", "--rev=HEAD"]),
+            [Some("@acme/old".to_string())]
+        );
+        assert_eq!(
+            owners(&repository, &["--by-owner"]),
+            [Some("@acme/new".to_string())]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use crate::{
+    context::AppContext,
+    core::{
+        CompositionEntry, DirectoryComposition, detect_packages::detect_packages, git::GitError,
+    },
+};
+
+#[derive(Debug)]
+pub struct PackageComposition {
+    /// `None` for lines outside of every detected package.
+    pub name: Option<String>,
+    /// Directory of the package manifest, relative to the scan root.
+    pub root: PathBuf,
+    pub entries: Vec<CompositionEntry>,
+}
+
+/// Rolls per-directory entries up into the packages detected below the scan root. Each directory
+/// belongs to the innermost package that contains it.
+pub fn get_package_composition(
+    app_context: &AppContext,
+    directories: &[DirectoryComposition],
+) -> Result<Vec<PackageComposition>, GitError> {
+    let mut packages: Vec<PackageComposition> = detect_packages(app_context)?
+        .into_iter()
+        .map(|package| PackageComposition {
+            name: Some(package.name),
+            root: package.root,
+            entries: Vec::new(),
+        })
+        .collect();
+    let mut unpackaged = PackageComposition {
+        name: None,
+        root: PathBuf::new(),
+        entries: Vec::new(),
+    };
+
+    for directory in directories {
+        let package = packages
+            .iter_mut()
+            .filter(|package| directory.path.starts_with(&package.root))
+            .max_by_key(|package| package.root.components().count())
+            .unwrap_or(&mut unpackaged);
+
+        for entry in &directory.entries {
+            match package
+                .entries
+                .iter_mut()
+                .find(|e| e.tracked.display == entry.tracked.display)
+            {
+                Some(existing) => existing.merge(entry),
+                None => package.entries.push(entry.clone()),
+            }
+        }
+    }
+
+    packages.push(unpackaged);
+    packages.retain(|package| !package.entries.is_empty());
+    Ok(packages)
+}
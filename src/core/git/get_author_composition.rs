@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use super::{GitError, get_blame_line_counts};
+use crate::context::{AppContext, config::Tracked};
+
+#[derive(Debug)]
+pub struct AuthorEntry {
+    pub name: String,
+    pub email: String,
+    pub line_counts: Vec<(Tracked, usize)>,
+}
+
+impl AuthorEntry {
+    pub fn line_count(&self) -> usize {
+        self.line_counts.iter().map(|(_, lines)| lines).sum()
+    }
+}
+
+/// Attributes the counted lines of `revision` to the author who last touched them, per language.
+///
+/// Authors are identified by name and email after `.mailmap` resolution.
+pub fn get_author_composition(
+    app_context: &AppContext,
+    revision: &str,
+) -> Result<Vec<AuthorEntry>, GitError> {
+    let line_counts = get_blame_line_counts(app_context, revision, |hunk| {
+        let signature = hunk.final_signature();
+        (
+            signature.name().unwrap_or("unknown").to_string(),
+            signature.email().unwrap_or_default().to_string(),
+        )
+    })?;
+
+    let mut lines_by_author: HashMap<(String, String), HashMap<usize, usize>> = HashMap::new();
+    for ((tracked_index, author), lines) in line_counts {
+        *lines_by_author
+            .entry(author)
+            .or_default()
+            .entry(tracked_index)
+            .or_default() += lines;
+    }
+
+    let entries = lines_by_author
+        .into_iter()
+        .map(|((name, email), lines_by_tracked)| {
+            let mut line_counts: Vec<(Tracked, usize)> = lines_by_tracked
+                .into_iter()
+                .map(|(index, lines)| (app_context.config.tracked[index].clone(), lines))
+                .collect();
+            line_counts.sort_by_key(|(_, lines)| std::cmp::Reverse(*lines));
+
+            AuthorEntry {
+                name,
+                email,
+                line_counts,
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod get_author_composition_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    fn lines_of(entries: &[AuthorEntry], name: &str) -> usize {
+        entries
+            .iter()
+            .find(|e| e.name == name)
+            .map_or(0, |e| e.line_count())
+    }
+
+    #[test]
+    fn lines_go_to_their_last_author_after_mailmap_resolution() {
+        let repository = TestRepository::new();
+        repository.write(
+            "main.rs",
+            "// not counted\nfn one() {}\nfn two() {}\nfn three() {}\n",
+        );
+        repository.commit("Alice", "2024-01-10");
+
+        repository.write(
+            "main.rs",
+            "// not counted\nfn one() {}\nfn two() { 2 }\nfn three() {}\nfn four() {}\n",
+        );
+        repository.write(
+            ".mailmap",
+            "Robert <bob@example.com> Bob <bob@example.com>\n",
+        );
+        repository.commit("Bob", "2024-02-10");
+
+        let app_context = repository.app_context(&[]);
+        let entries = get_author_composition(&app_context, "HEAD").unwrap();
+
+        assert_eq!(lines_of(&entries, "Alice"), 2);
+        assert_eq!(lines_of(&entries, "Robert"), 2);
+        assert_eq!(lines_of(&entries, "Bob"), 0);
+    }
+}
@@ -0,0 +1,111 @@
+use git2::{BlameHunk, BlameOptions, Oid, Repository};
+use rayon::prelude::*;
+use std::{collections::HashMap, hash::Hash, path::Path};
+
+use super::{GitError, canonical_workdir, open_repository, repository_path, repository_prefix};
+use crate::{
+    context::AppContext,
+    core::{
+        SourceFile, get_revision_entries, is_counted_line, is_first_party, with_thread_repository,
+    },
+};
+
+/// Counted lines of `revision`, keyed by the index of their language in `config.tracked` and a
+/// key derived from the blame hunk each line belongs to.
+///
+/// Only hand-written, first-party lines are blamed, using the same counting rules as
+/// `get_composition`. Author identities are resolved through the repository's `.mailmap`.
+pub fn get_blame_line_counts<K, F>(
+    app_context: &AppContext,
+    revision: &str,
+    key: F,
+) -> Result<HashMap<(usize, K), usize>, GitError>
+where
+    K: Eq + Hash + Send,
+    F: Fn(&BlameHunk) -> K + Sync,
+{
+    let canonical_root = app_context.canonical_root();
+    let repository = open_repository(&canonical_root)?;
+    let workdir = canonical_workdir(&repository)?;
+    let prefix = repository_prefix(&repository, &canonical_root)?;
+
+    let commit_id = repository
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|_| GitError::RevisionNotFound(revision.to_string()))?;
+
+    let files = get_revision_entries(app_context, &commit_id.to_string())?;
+
+    // a worker thread that cannot open the repository fails the whole run
+    files
+        .par_iter()
+        .map(|file| {
+            with_thread_repository(&workdir, |repository| {
+                blame_file(app_context, repository, &prefix, commit_id, file, &key)
+            })?
+        })
+        .try_reduce(HashMap::new, |mut counts, other| {
+            for (key, lines) in other {
+                *counts.entry(key).or_default() += lines;
+            }
+            Ok(counts)
+        })
+}
+
+fn blame_file<K, F>(
+    app_context: &AppContext,
+    repository: &Repository,
+    prefix: &Path,
+    commit_id: Oid,
+    file: &SourceFile,
+    key: &F,
+) -> Result<HashMap<(usize, K), usize>, GitError>
+where
+    K: Eq + Hash,
+    F: Fn(&BlameHunk) -> K,
+{
+    let mut counts = HashMap::new();
+
+    let Some(ext) = file.path.extension().and_then(|ext| ext.to_str()) else {
+        return Ok(counts);
+    };
+    let ext = ext.to_lowercase();
+
+    let tracked_index = app_context
+        .config
+        .tracked
+        .iter()
+        .position(|t| t.extensions.contains(&ext));
+    let (Some(tracked_index), Some(blob_id)) = (tracked_index, file.blob_id) else {
+        return Ok(counts);
+    };
+    let tracked = &app_context.config.tracked[tracked_index];
+
+    let blob = repository.find_blob(blob_id)?;
+    if !is_first_party(&file.path, blob.content(), app_context) {
+        return Ok(counts);
+    }
+    let Ok(content) = std::str::from_utf8(blob.content()) else {
+        return Ok(counts);
+    };
+
+    // blame works on paths relative to the repository root
+    let repository_path = repository_path(app_context, prefix, &file.path);
+
+    let mut options = BlameOptions::new();
+    options.newest_commit(commit_id).use_mailmap(true);
+    let blame = repository.blame_file(&repository_path, Some(&mut options))?;
+
+    for (index, line) in content.lines().enumerate() {
+        if !is_counted_line(line, app_context, tracked) {
+            continue;
+        }
+
+        if let Some(hunk) = blame.get_line(index + 1) {
+            *counts.entry((tracked_index, key(&hunk))).or_default() += 1;
+        }
+    }
+
+    Ok(counts)
+}
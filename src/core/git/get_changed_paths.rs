@@ -0,0 +1,124 @@
+use git2::{Delta, DiffOptions};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use super::{GitError, canonical_workdir, open_repository, repository_prefix};
+use crate::context::AppContext;
+
+/// Lists the files under the scan root that differ from `base` in the working tree: changes
+/// committed since `base`, staged and unstaged changes, and untracked files that are not ignored.
+///
+/// The comparison starts at the merge base of `base` and `HEAD`, so commits that only landed on
+/// `base` after the current branch forked from it are not reported. Without a common ancestor,
+/// `base` itself is used.
+///
+/// Returned paths are rooted at `app_context.path`, the same way a directory walk would yield them.
+pub fn get_changed_paths(
+    app_context: &AppContext,
+    canonical_root: &Path,
+    base: &str,
+) -> Result<HashSet<PathBuf>, GitError> {
+    let repository = open_repository(canonical_root)?;
+    let workdir = canonical_workdir(&repository)?;
+    let prefix = repository_prefix(&repository, canonical_root)?;
+
+    // a repository without commits has everything uncommitted
+    let base_commit = match repository.revparse_single(base) {
+        Ok(object) => Some(object.peel_to_commit()?),
+        Err(_) if repository.is_empty()? => None,
+        Err(_) => return Err(GitError::RevisionNotFound(base.to_string())),
+    };
+
+    let base_tree = match base_commit {
+        Some(base_commit) => {
+            let head_id = repository.head()?.peel_to_commit()?.id();
+            let fork_point = repository
+                .merge_base(base_commit.id(), head_id)
+                .unwrap_or(base_commit.id());
+            Some(repository.find_commit(fork_point)?.tree()?)
+        }
+        None => None,
+    };
+
+    let mut options = DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    if !prefix.as_os_str().is_empty() {
+        options.pathspec(&prefix);
+    }
+
+    let diff =
+        repository.diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut options))?;
+
+    let paths = diff
+        .deltas()
+        .filter(|delta| delta.status() != Delta::Deleted)
+        .filter_map(|delta| {
+            let path = workdir.join(delta.new_file().path()?);
+            let relative_path = path.strip_prefix(canonical_root).ok()?;
+            Some(app_context.path.join(relative_path))
+        })
+        .collect();
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod get_changed_paths_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+    use git2::ResetType;
+
+    fn changed_paths(repository: &TestRepository, base: &str) -> Vec<PathBuf> {
+        let app_context = repository.app_context(&[]);
+        let mut paths: Vec<_> = get_changed_paths(&app_context, &repository.root, base)
+            .unwrap()
+            .into_iter()
+            .map(|path| path.strip_prefix(&app_context.path).unwrap().to_path_buf())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn changes_are_taken_from_the_fork_point_of_the_base() {
+        let repository = TestRepository::new();
+        repository.write("shared.rs", "fn shared() {}\n");
+        let fork_point = repository.commit("Alice", "2024-01-10");
+
+        // main moves on after the branch forked from it
+        repository.write("shared.rs", "fn shared() { 2 }\n");
+        let main = repository.commit("Alice", "2024-01-20");
+        let main = repository.repository.find_commit(main).unwrap();
+        repository.repository.branch("main", &main, true).unwrap();
+        let fork_point = repository.repository.find_object(fork_point, None).unwrap();
+        repository
+            .repository
+            .reset(&fork_point, ResetType::Hard, None)
+            .unwrap();
+
+        repository.write("feature.rs", "fn feature() {}\n");
+        repository.commit("Bob", "2024-01-15");
+        repository.write("untracked.rs", "fn untracked() {}\n");
+
+        assert_eq!(
+            changed_paths(&repository, "main"),
+            [PathBuf::from("feature.rs"), PathBuf::from("untracked.rs")]
+        );
+    }
+
+    #[test]
+    fn head_as_base_lists_uncommitted_changes_only() {
+        let repository = TestRepository::new();
+        repository.write("committed.rs", "fn committed() {}\n");
+        repository.write("edited.rs", "fn edited() {}\n");
+        repository.commit("Alice", "2024-01-10");
+        repository.write("edited.rs", "fn edited() { 2 }\n");
+
+        assert_eq!(
+            changed_paths(&repository, "HEAD"),
+            [PathBuf::from("edited.rs")]
+        );
+    }
+}
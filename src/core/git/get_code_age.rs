@@ -0,0 +1,124 @@
+use chrono::{DateTime, Datelike};
+use std::collections::HashMap;
+
+use super::{GitError, get_blame_line_counts};
+use crate::context::{AppContext, config::Tracked};
+
+/// Period that lines are grouped into by the date of their last modification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeBucket {
+    Year,
+    Quarter,
+}
+
+#[derive(Debug)]
+pub struct CodeAgeEntry {
+    pub tracked: Tracked,
+    /// Counted lines per period of their last modification, oldest period first.
+    pub line_counts: Vec<(String, usize)>,
+}
+
+impl CodeAgeEntry {
+    pub fn line_count(&self) -> usize {
+        self.line_counts.iter().map(|(_, lines)| lines).sum()
+    }
+}
+
+/// Groups the counted lines of `revision` by language and the year or quarter in which blame
+/// says they were last modified.
+pub fn get_code_age(
+    app_context: &AppContext,
+    revision: &str,
+    period: AgeBucket,
+) -> Result<Vec<CodeAgeEntry>, GitError> {
+    let line_counts = get_blame_line_counts(app_context, revision, |hunk| {
+        period_label(hunk.final_signature().when().seconds(), period)
+    })?;
+
+    let mut lines_by_tracked: HashMap<usize, Vec<(String, usize)>> = HashMap::new();
+    for ((tracked_index, label), lines) in line_counts {
+        lines_by_tracked
+            .entry(tracked_index)
+            .or_default()
+            .push((label, lines));
+    }
+
+    let entries = lines_by_tracked
+        .into_iter()
+        .map(|(index, mut line_counts)| {
+            // labels are zero padded, so they sort chronologically
+            line_counts.sort();
+            CodeAgeEntry {
+                tracked: app_context.config.tracked[index].clone(),
+                line_counts,
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn period_label(seconds: i64, period: AgeBucket) -> String {
+    let Some(time) = DateTime::from_timestamp(seconds, 0) else {
+        return "unknown".into();
+    };
+
+    match period {
+        AgeBucket::Year => format!("{}", time.year()),
+        AgeBucket::Quarter => format!("{}-Q{}", time.year(), time.month0() / 3 + 1),
+    }
+}
+
+#[cfg(test)]
+mod get_code_age_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    fn line_counts_of<'a>(entries: &'a [CodeAgeEntry], display: &str) -> &'a [(String, usize)] {
+        &entries
+            .iter()
+            .find(|e| e.tracked.display == display)
+            .unwrap()
+            .line_counts
+    }
+
+    fn timestamp(value: &str) -> i64 {
+        DateTime::parse_from_rfc3339(value).unwrap().timestamp()
+    }
+
+    #[test]
+    fn lines_are_grouped_by_the_period_of_their_last_change() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\nfn two() {}\n");
+        repository.commit("Alice", "2023-11-10");
+        repository.write(
+            "main.rs",
+            "// not counted\nfn one() {}\nfn two() { 2 }\nfn three() {}\n",
+        );
+        repository.commit("Alice", "2024-05-10");
+
+        let app_context = repository.app_context(&[]);
+
+        let entries = get_code_age(&app_context, "HEAD", AgeBucket::Year).unwrap();
+        assert_eq!(
+            line_counts_of(&entries, "Rust"),
+            [("2023".to_string(), 1), ("2024".to_string(), 2)]
+        );
+
+        let entries = get_code_age(&app_context, "HEAD", AgeBucket::Quarter).unwrap();
+        assert_eq!(
+            line_counts_of(&entries, "Rust"),
+            [("2023-Q4".to_string(), 1), ("2024-Q2".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn quarters_start_in_january_april_july_and_october() {
+        let label = |value| period_label(timestamp(value), AgeBucket::Quarter);
+
+        assert_eq!(label("2024-03-31T23:59:59Z"), "2024-Q1");
+        assert_eq!(label("2024-04-01T00:00:00Z"), "2024-Q2");
+        assert_eq!(label("2024-09-30T12:00:00Z"), "2024-Q3");
+        assert_eq!(label("2024-10-01T00:00:00Z"), "2024-Q4");
+    }
+}
@@ -0,0 +1,349 @@
+use git2::{DiffFindOptions, DiffOptions, FileMode, Oid, Repository};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use super::{GitError, open_repository, repository_prefix};
+use crate::{
+    context::{AppContext, config::Tracked},
+    core::{
+        SourceFile, filter_revision_entries, find_tracked, get_composition, get_revision_entries,
+        is_counted_line, is_first_party,
+    },
+};
+
+#[derive(Debug)]
+pub struct CompositionDiffEntry {
+    pub tracked: Tracked,
+    pub added: usize,
+    pub removed: usize,
+    pub base_percentage: f32,
+    pub head_percentage: f32,
+}
+
+impl CompositionDiffEntry {
+    pub fn net(&self) -> isize {
+        self.added as isize - self.removed as isize
+    }
+
+    pub fn percentage_point_shift(&self) -> f32 {
+        self.head_percentage - self.base_percentage
+    }
+}
+
+/// Computes per-language line deltas for a revision range such as `main..HEAD` or `main...HEAD`.
+///
+/// Added and removed lines are taken from the changed blobs only, using the same counting rules
+/// as `get_composition`, and renamed files are paired up so that only their edits count. The
+/// base revision is counted in full to derive the percentage shares on both sides.
+pub fn get_composition_diff(
+    app_context: &AppContext,
+    range: &str,
+) -> Result<Vec<CompositionDiffEntry>, GitError> {
+    let canonical_root = app_context.canonical_root();
+    let repository = open_repository(&canonical_root)?;
+    let prefix = repository_prefix(&repository, &canonical_root)?;
+    let (base_id, head_id) = resolve_range(&repository, range)?;
+
+    let base_tree = repository.find_commit(base_id)?.tree()?;
+    let head_tree = repository.find_commit(head_id)?.tree()?;
+
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    if !prefix.as_os_str().is_empty() {
+        options.pathspec(&prefix);
+    }
+
+    let mut diff =
+        repository.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut options))?;
+
+    // a moved file would otherwise show up as all of its lines removed and added again
+    let mut find_options = DiffFindOptions::new();
+    find_options.renames(true);
+    diff.find_similar(Some(&mut find_options))?;
+
+    // changed files that pass the entry filters on either side of the range
+    let mut base_changed = Vec::new();
+    let mut head_changed = Vec::new();
+    for delta in diff.deltas() {
+        for (file, changed) in [
+            (delta.old_file(), &mut base_changed),
+            (delta.new_file(), &mut head_changed),
+        ] {
+            if file.id().is_zero()
+                || !matches!(file.mode(), FileMode::Blob | FileMode::BlobExecutable)
+            {
+                continue;
+            }
+
+            if let Some(path) = file.path() {
+                let relative_path = path.strip_prefix(&prefix).unwrap_or(path);
+                changed.push(SourceFile::in_repository(
+                    app_context.path.join(relative_path),
+                    file.id(),
+                ));
+            }
+        }
+    }
+    let counted_paths = |files: Vec<SourceFile>| -> HashSet<PathBuf> {
+        filter_revision_entries(app_context, files)
+            .into_iter()
+            .map(|file| file.path)
+            .collect()
+    };
+    let base_paths = counted_paths(base_changed);
+    let head_paths = counted_paths(head_changed);
+
+    let mut first_party_blobs: HashMap<Oid, bool> = HashMap::new();
+    let mut changes_by_display: HashMap<String, (usize, usize)> = HashMap::new();
+
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        None,
+        Some(&mut |delta, _, line| {
+            let (file, paths) = match line.origin() {
+                '+' => (delta.new_file(), &head_paths),
+                '-' => (delta.old_file(), &base_paths),
+                _ => return true,
+            };
+
+            let Some(path) = file.path() else {
+                return true;
+            };
+
+            let relative_path = path.strip_prefix(&prefix).unwrap_or(path);
+            let path = app_context.path.join(relative_path);
+            if !paths.contains(&path) {
+                return true;
+            }
+
+            // binary, minified, generated and vendored files never count as changed lines
+            let is_counted_file = *first_party_blobs.entry(file.id()).or_insert_with(|| {
+                repository
+                    .find_blob(file.id())
+                    .map(|blob| is_first_party(&path, blob.content(), app_context))
+                    .unwrap_or(false)
+            });
+
+            if !is_counted_file {
+                return true;
+            }
+
+            let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+                return true;
+            };
+
+            let Some(tracked) = find_tracked(app_context, &ext.to_lowercase()) else {
+                return true;
+            };
+
+            let Ok(content) = std::str::from_utf8(line.content()) else {
+                return true;
+            };
+
+            if !is_counted_line(content.trim_end_matches(['\n', '\r']), app_context, tracked) {
+                return true;
+            }
+
+            let changes = changes_by_display
+                .entry(tracked.display.clone())
+                .or_default();
+            if line.origin() == '+' {
+                changes.0 += 1;
+            } else {
+                changes.1 += 1;
+            }
+
+            true
+        }),
+    )?;
+
+    let base_files = get_revision_entries(app_context, &base_id.to_string())?;
+    let base_line_counts: HashMap<String, usize> = get_composition(app_context, base_files)
+        .entries
+        .into_iter()
+        .map(|entry| (entry.tracked.display, entry.line_count))
+        .collect();
+
+    // head totals are derived from the base totals and the line deltas
+    let config = &app_context.config;
+    let base_total: usize = config
+        .tracked
+        .iter()
+        .filter(|tracked| config.counts_towards_percentage(tracked))
+        .filter_map(|tracked| base_line_counts.get(&tracked.display))
+        .sum();
+    let head_total: usize = config
+        .tracked
+        .iter()
+        .filter(|tracked| config.counts_towards_percentage(tracked))
+        .map(|tracked| {
+            let base = base_line_counts.get(&tracked.display).copied().unwrap_or(0);
+            let (added, removed) = changes_by_display
+                .get(&tracked.display)
+                .copied()
+                .unwrap_or_default();
+            (base + added).saturating_sub(removed)
+        })
+        .sum();
+
+    let entries = app_context
+        .config
+        .tracked
+        .iter()
+        .filter_map(|tracked| {
+            let (added, removed) = changes_by_display.get(&tracked.display).copied()?;
+            let base = base_line_counts.get(&tracked.display).copied().unwrap_or(0);
+            let head = (base + added).saturating_sub(removed);
+
+            // languages excluded from the percentages keep a share of zero on both sides
+            let (base_percentage, head_percentage) = if config.counts_towards_percentage(tracked) {
+                (percentage(base, base_total), percentage(head, head_total))
+            } else {
+                (0.0, 0.0)
+            };
+
+            Some(CompositionDiffEntry {
+                tracked: tracked.clone(),
+                added,
+                removed,
+                base_percentage,
+                head_percentage,
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn percentage(lines: usize, total: usize) -> f32 {
+    (lines as f32 / total.max(1) as f32) * 100.0
+}
+
+// `base..head` compares the two commits, `base...head` compares head against the merge base,
+// a single revision is compared against HEAD
+fn resolve_range(repository: &Repository, range: &str) -> Result<(Oid, Oid), GitError> {
+    let resolve = |revision: &str| {
+        let revision = if revision.is_empty() {
+            "HEAD"
+        } else {
+            revision
+        };
+        repository
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id())
+            .map_err(|_| GitError::RevisionNotFound(revision.to_string()))
+    };
+
+    if let Some((base, head)) = range.split_once("...") {
+        let (base, head) = (resolve(base)?, resolve(head)?);
+        return Ok((repository.merge_base(base, head)?, head));
+    }
+
+    if let Some((base, head)) = range.split_once("..") {
+        return Ok((resolve(base)?, resolve(head)?));
+    }
+
+    Ok((resolve(range)?, resolve("HEAD")?))
+}
+
+#[cfg(test)]
+mod get_composition_diff_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    fn entry<'a>(entries: &'a [CompositionDiffEntry], display: &str) -> &'a CompositionDiffEntry {
+        entries
+            .iter()
+            .find(|e| e.tracked.display == display)
+            .unwrap()
+    }
+
+    fn two_commits() -> TestRepository {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\nfn two() {}\n");
+        repository.write("script.py", "one = 1\ntwo = 2\n");
+        repository.commit("Alice", "2024-01-10");
+
+        repository.write(
+            "main.rs",
+            "// not counted\nfn one() {}\nfn two() {}\nfn three() {}\n",
+        );
+        repository.write("script.py", "one = 1\n");
+        repository.write("vendor/lib.rs", "fn vendored() {}\n");
+        repository.commit("Alice", "2024-02-10");
+        repository
+    }
+
+    #[test]
+    fn counts_changed_lines_of_counted_files_only() {
+        let repository = two_commits();
+        let app_context = repository.app_context(&[]);
+
+        let entries = get_composition_diff(&app_context, "HEAD~1..HEAD").unwrap();
+
+        assert_eq!(entries.len(), 2);
+        let rust = entry(&entries, "Rust");
+        assert_eq!((rust.added, rust.removed), (1, 0));
+        let python = entry(&entries, "Python");
+        assert_eq!((python.added, python.removed), (0, 1));
+    }
+
+    #[test]
+    fn shares_are_computed_on_both_sides() {
+        let repository = two_commits();
+        let app_context = repository.app_context(&[]);
+
+        let entries = get_composition_diff(&app_context, "HEAD~1..HEAD").unwrap();
+
+        let rust = entry(&entries, "Rust");
+        assert_eq!(rust.base_percentage, 50.0);
+        assert_eq!(rust.head_percentage, 75.0);
+        assert_eq!(rust.percentage_point_shift(), 25.0);
+    }
+
+    #[test]
+    fn renamed_files_only_count_their_edits() {
+        let repository = TestRepository::new();
+        repository.write(
+            "lib.rs",
+            "fn one() {}\nfn two() {}\nfn three() {}\nfn four() {}\n",
+        );
+        repository.commit("Alice", "2024-01-10");
+
+        std::fs::remove_file(repository.root.join("lib.rs")).unwrap();
+        repository.write(
+            "src/lib.rs",
+            "fn one() {}\nfn two() {}\nfn three() {}\nfn four() {}\nfn five() {}\n",
+        );
+        repository.commit("Alice", "2024-02-10");
+        let app_context = repository.app_context(&[]);
+
+        let entries = get_composition_diff(&app_context, "HEAD~1..HEAD").unwrap();
+
+        let rust = entry(&entries, "Rust");
+        assert_eq!((rust.added, rust.removed), (1, 0));
+        assert_eq!(rust.head_percentage, 100.0);
+    }
+
+    #[test]
+    fn minified_files_count_when_they_are_not_ignored() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\n");
+        repository.commit("Alice", "2024-01-10");
+        repository.write("app.js", &format!("{}\n", "a=1;b=2;".repeat(200)));
+        repository.commit("Alice", "2024-02-10");
+
+        let ignored = repository.app_context(&[]);
+        let entries = get_composition_diff(&ignored, "HEAD~1..HEAD").unwrap();
+        assert!(entries.is_empty());
+
+        let counted = repository.app_context(&["--ignore-minified-files=false"]);
+        let entries = get_composition_diff(&counted, "HEAD~1..HEAD").unwrap();
+        let javascript = entry(&entries, "JavaScript");
+        assert_eq!((javascript.added, javascript.removed), (1, 0));
+    }
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
+use git2::{Oid, Repository};
+
+use super::{GitError, open_repository};
+use crate::{
+    context::AppContext,
+    core::{CompositionEntry, CountCache, get_composition_cached, get_revision_entries},
+};
+
+/// Interval between two history samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleStep {
+    Days(u64),
+    Months(u32),
+}
+
+#[derive(Debug)]
+pub struct HistorySample {
+    pub date: NaiveDate,
+    pub commit_id: Oid,
+    pub entries: Vec<CompositionEntry>,
+}
+
+/// Samples the first-parent history of `revision` every `step`, starting at `since`.
+///
+/// Each sample holds the composition of the newest commit made before the sample date. A final
+/// sample for `revision` itself is added when it is newer than the last sampled commit. Blob
+/// results are cached across samples, so only files that changed in between are read again.
+pub fn get_composition_history(
+    app_context: &AppContext,
+    revision: &str,
+    since: NaiveDate,
+    until: Option<NaiveDate>,
+    step: SampleStep,
+) -> Result<Vec<HistorySample>, GitError> {
+    let canonical_root = app_context.canonical_root();
+    let repository = open_repository(&canonical_root)?;
+    let commits = first_parent_commits(&repository, revision, start_of_day(since))?;
+
+    let Some(&(head_id, head_time)) = commits.first() else {
+        return Ok(Vec::new());
+    };
+
+    // (sample date, commit) pairs in chronological order
+    let until = until.unwrap_or_else(|| Utc::now().date_naive());
+    let mut sample_points: Vec<(NaiveDate, Oid)> = Vec::new();
+    let sample_dates = (0..).map_while(|index| sample_date(since, step, index));
+    for date in sample_dates.take_while(|date| *date <= until) {
+        let boundary = start_of_day(date);
+        if let Some(&(commit_id, _)) = commits.iter().find(|(_, time)| *time < boundary) {
+            sample_points.push((date, commit_id));
+        }
+    }
+
+    let head_date = DateTime::from_timestamp(head_time, 0)
+        .map(|time| time.date_naive())
+        .unwrap_or(until);
+    if head_date <= until && sample_points.last().is_none_or(|(_, id)| *id != head_id) {
+        sample_points.push((head_date, head_id));
+    }
+
+    let mut cache = CountCache::default();
+    let mut samples: Vec<HistorySample> = Vec::with_capacity(sample_points.len());
+    for (date, commit_id) in sample_points {
+        // unchanged stretches of history reuse the previous sample
+        let entries = match samples.last() {
+            Some(previous) if previous.commit_id == commit_id => previous.entries.clone(),
+            _ => {
+                let files = get_revision_entries(app_context, &commit_id.to_string())?;
+                let mut entries = get_composition_cached(app_context, files, &mut cache).entries;
+                set_percentages(app_context, &mut entries);
+                entries
+            }
+        };
+
+        samples.push(HistorySample {
+            date,
+            commit_id,
+            entries,
+        });
+    }
+
+    Ok(samples)
+}
+
+// the first-parent chain of `revision` from newest to oldest, with commit times in seconds,
+// down to and including the first commit made before `since`
+fn first_parent_commits(
+    repository: &Repository,
+    revision: &str,
+    since: i64,
+) -> Result<Vec<(Oid, i64)>, GitError> {
+    let head = repository
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| GitError::RevisionNotFound(revision.to_string()))?;
+
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push(head.id())?;
+    revwalk.simplify_first_parent()?;
+
+    let mut commits = Vec::new();
+    for commit_id in revwalk {
+        let commit_id = commit_id?;
+        let time = repository.find_commit(commit_id)?.time().seconds();
+        commits.push((commit_id, time));
+
+        if time < since {
+            break;
+        }
+    }
+
+    Ok(commits)
+}
+
+// the `index`-th sample date, counted from `since` so that month ends do not drift
+fn sample_date(since: NaiveDate, step: SampleStep, index: u32) -> Option<NaiveDate> {
+    match step {
+        SampleStep::Days(days) => since.checked_add_days(Days::new(days * u64::from(index))),
+        SampleStep::Months(months) => {
+            since.checked_add_months(Months::new(months.checked_mul(index)?))
+        }
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|time| time.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+// entries in categories excluded from the percentages keep a share of zero
+fn set_percentages(app_context: &AppContext, entries: &mut [CompositionEntry]) {
+    let config = &app_context.config;
+    let total_lines: usize = entries
+        .iter()
+        .filter(|e| config.counts_towards_percentage(&e.tracked))
+        .map(|e| e.line_count)
+        .sum();
+    for entry in entries {
+        if config.counts_towards_percentage(&entry.tracked) {
+            entry.percentage = (entry.line_count as f32 / total_lines.max(1) as f32) * 100.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod get_composition_history_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn percentage_of(sample: &HistorySample, display: &str) -> f32 {
+        sample
+            .entries
+            .iter()
+            .find(|e| e.tracked.display == display)
+            .map_or(0.0, |e| e.percentage)
+    }
+
+    #[test]
+    fn samples_hold_the_newest_commit_before_each_date() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\nfn two() {}\n");
+        let first = repository.commit("Alice", "2024-01-10");
+        repository.write("script.py", "one = 1\ntwo = 2\n");
+        let second = repository.commit("Alice", "2024-03-10");
+
+        let app_context = repository.app_context(&[]);
+        let samples = get_composition_history(
+            &app_context,
+            "HEAD",
+            date("2024-01-01"),
+            Some(date("2024-04-01")),
+            SampleStep::Months(1),
+        )
+        .unwrap();
+
+        // nothing was committed before the first of January
+        let dates: Vec<_> = samples.iter().map(|s| s.date).collect();
+        assert_eq!(
+            dates,
+            [date("2024-02-01"), date("2024-03-01"), date("2024-04-01")]
+        );
+        let commits: Vec<_> = samples.iter().map(|s| s.commit_id).collect();
+        assert_eq!(commits, [first, first, second]);
+
+        assert_eq!(percentage_of(&samples[0], "Rust"), 100.0);
+        assert_eq!(percentage_of(&samples[2], "Rust"), 50.0);
+        assert_eq!(percentage_of(&samples[2], "Python"), 50.0);
+    }
+
+    #[test]
+    fn a_final_sample_is_taken_for_a_newer_revision() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\n");
+        let first = repository.commit("Alice", "2024-01-05");
+        repository.write("main.rs", "fn one() {}\nfn two() {}\n");
+        let second = repository.commit("Alice", "2024-01-20");
+
+        let app_context = repository.app_context(&[]);
+        let samples = get_composition_history(
+            &app_context,
+            "HEAD",
+            date("2024-01-01"),
+            Some(date("2024-01-21")),
+            SampleStep::Days(7),
+        )
+        .unwrap();
+
+        // the weekly samples on the 8th and 15th still see the first commit
+        let points: Vec<_> = samples.iter().map(|s| (s.date, s.commit_id)).collect();
+        assert_eq!(
+            points,
+            [
+                (date("2024-01-08"), first),
+                (date("2024-01-15"), first),
+                (date("2024-01-20"), second),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_steps_clamp_to_the_end_of_shorter_months() {
+        assert_eq!(
+            sample_date(date("2024-01-31"), SampleStep::Months(1), 1),
+            Some(date("2024-02-29"))
+        );
+        assert_eq!(
+            sample_date(date("2024-02-26"), SampleStep::Days(7), 1),
+            Some(date("2024-03-04"))
+        );
+    }
+
+    #[test]
+    fn monthly_samples_from_a_month_end_do_not_drift() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\n");
+        repository.commit("Alice", "2023-01-02");
+
+        let app_context = repository.app_context(&[]);
+        let samples = get_composition_history(
+            &app_context,
+            "HEAD",
+            date("2023-01-31"),
+            Some(date("2023-05-31")),
+            SampleStep::Months(1),
+        )
+        .unwrap();
+
+        let dates: Vec<NaiveDate> = samples.iter().map(|sample| sample.date).collect();
+        assert_eq!(
+            dates,
+            [
+                date("2023-01-31"),
+                date("2023-02-28"),
+                date("2023-03-31"),
+                date("2023-04-30"),
+                date("2023-05-31"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::{Path, PathBuf};
+
+use super::open_repository;
+
+/// The excludes file git applies on top of the repository's own ignore files.
+///
+/// This is `core.excludesFile` as git reads it, from the system, global or repository config,
+/// defaulting to `$XDG_CONFIG_HOME/git/ignore` (or `~/.config/git/ignore`) when it is not set.
+pub fn get_excludes_file(canonical_root: &Path) -> Option<PathBuf> {
+    let configured = open_repository(canonical_root)
+        .ok()
+        .and_then(|repository| repository.config().ok())
+        .and_then(|config| config.get_path("core.excludesFile").ok());
+
+    configured.or_else(|| {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+        Some(config_home.join("git").join("ignore"))
+    })
+}
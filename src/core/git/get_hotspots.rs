@@ -0,0 +1,178 @@
+use chrono::NaiveDate;
+use git2::{DiffOptions, Patch, Sort};
+use std::{collections::HashMap, path::PathBuf};
+
+use super::{GitError, open_repository, repository_path, repository_prefix};
+use crate::{
+    context::{AppContext, config::Tracked},
+    core::{get_file_line_counts, get_revision_entries},
+};
+
+#[derive(Debug)]
+pub struct Hotspot {
+    pub path: PathBuf,
+    pub tracked: Tracked,
+    pub line_count: usize,
+    pub commit_count: usize,
+    pub lines_changed: usize,
+}
+
+impl Hotspot {
+    /// Large files that change often rank highest.
+    pub fn score(&self) -> usize {
+        self.line_count * self.commit_count
+    }
+}
+
+/// Combines the counted lines of each file in `revision` with the number of non-merge commits
+/// that touched it since `since`, and the lines those commits added and removed.
+///
+/// Files that were not changed in the window are left out.
+pub fn get_hotspots(
+    app_context: &AppContext,
+    revision: &str,
+    since: NaiveDate,
+) -> Result<Vec<Hotspot>, GitError> {
+    let canonical_root = app_context.canonical_root();
+    let repository = open_repository(&canonical_root)?;
+    let prefix = repository_prefix(&repository, &canonical_root)?;
+
+    let head = repository
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| GitError::RevisionNotFound(revision.to_string()))?;
+
+    let since = since
+        .and_hms_opt(0, 0, 0)
+        .map(|time| time.and_utc().timestamp())
+        .unwrap_or_default();
+
+    let mut revwalk = repository.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    revwalk.push(head.id())?;
+
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    if !prefix.as_os_str().is_empty() {
+        options.pathspec(&prefix);
+    }
+
+    // (commits, lines changed) per path relative to the repository root
+    let mut churn: HashMap<PathBuf, (usize, usize)> = HashMap::new();
+    for commit_id in revwalk {
+        let commit = repository.find_commit(commit_id?)?;
+        if commit.time().seconds() < since {
+            break;
+        }
+
+        // merges repeat the changes of the commits they bring in
+        if commit.parent_count() > 1 {
+            continue;
+        }
+
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repository.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut options),
+        )?;
+
+        for (index, delta) in diff.deltas().enumerate() {
+            let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+
+            let lines_changed = match Patch::from_diff(&diff, index)? {
+                Some(patch) => {
+                    let (_, additions, deletions) = patch.line_stats()?;
+                    additions + deletions
+                }
+                None => 0,
+            };
+
+            let entry = churn.entry(path.to_path_buf()).or_default();
+            entry.0 += 1;
+            entry.1 += lines_changed;
+        }
+    }
+
+    let files = get_revision_entries(app_context, &head.id().to_string())?;
+    let hotspots = get_file_line_counts(app_context, files)
+        .into_iter()
+        .filter_map(|file| {
+            let repository_path = repository_path(app_context, &prefix, &file.path);
+            let &(commit_count, lines_changed) = churn.get(&repository_path)?;
+
+            Some(Hotspot {
+                path: file.path,
+                tracked: file.tracked,
+                line_count: file.line_count,
+                commit_count,
+                lines_changed,
+            })
+        })
+        .collect();
+
+    Ok(hotspots)
+}
+
+#[cfg(test)]
+mod get_hotspots_tests {
+    use super::*;
+    use crate::core::git::test_repository::TestRepository;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn history() -> TestRepository {
+        let repository = TestRepository::new();
+        repository.write("busy.rs", "fn one() {}\nfn two() {}\n");
+        repository.write("quiet.rs", "fn quiet() {}\n");
+        repository.commit("Alice", "2024-01-10");
+        repository.write("busy.rs", "fn one() {}\nfn two() { 2 }\n");
+        repository.commit("Alice", "2024-02-10");
+        repository.write("busy.rs", "fn one() {}\nfn two() { 2 }\nfn three() {}\n");
+        repository.commit("Bob", "2024-03-10");
+        repository
+    }
+
+    #[test]
+    fn churn_is_counted_per_file_within_the_window() {
+        let repository = history();
+        let app_context = repository.app_context(&[]);
+
+        let hotspots = get_hotspots(&app_context, "HEAD", date("2024-02-01")).unwrap();
+
+        // the quiet file was not touched since February
+        assert_eq!(hotspots.len(), 1);
+        let busy = &hotspots[0];
+        assert!(busy.path.ends_with("busy.rs"));
+        assert_eq!(busy.line_count, 3);
+        assert_eq!(busy.commit_count, 2);
+        assert_eq!(busy.lines_changed, 3);
+        assert_eq!(busy.score(), 6);
+    }
+
+    #[test]
+    fn the_first_commit_counts_as_a_change() {
+        let repository = history();
+        let app_context = repository.app_context(&[]);
+
+        let hotspots = get_hotspots(&app_context, "HEAD", date("2024-01-01")).unwrap();
+
+        let quiet = hotspots
+            .iter()
+            .find(|h| h.path.ends_with("quiet.rs"))
+            .unwrap();
+        assert_eq!((quiet.commit_count, quiet.lines_changed), (1, 1));
+        let busy = hotspots
+            .iter()
+            .find(|h| h.path.ends_with("busy.rs"))
+            .unwrap();
+        assert_eq!((busy.commit_count, busy.lines_changed), (3, 5));
+    }
+}
@@ -0,0 +1,52 @@
+use git2::{ObjectType, TreeWalkMode, TreeWalkResult};
+use std::path::{Path, PathBuf};
+
+use super::{GitError, is_regular_file_mode, open_repository, repository_prefix};
+use crate::{context::AppContext, core::SourceFile};
+
+/// Lists the blobs of `revision` that live under the scan root, without touching the working tree.
+///
+/// Returned paths are rooted at `app_context.path`, the same way a directory walk would yield them.
+pub fn get_revision_files(
+    app_context: &AppContext,
+    canonical_root: &Path,
+    revision: &str,
+) -> Result<Vec<SourceFile>, GitError> {
+    let repository = open_repository(canonical_root)?;
+    let prefix = repository_prefix(&repository, canonical_root)?;
+
+    let tree = repository
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|_| GitError::RevisionNotFound(revision.to_string()))?;
+
+    // only walk the subtree that corresponds to the scan root
+    let tree = if prefix.as_os_str().is_empty() {
+        tree
+    } else {
+        match tree.get_path(&prefix) {
+            Ok(entry) => entry.to_object(&repository)?.peel_to_tree()?,
+            Err(_) => return Ok(Vec::new()),
+        }
+    };
+
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |directory, entry| {
+        if entry.kind() != Some(ObjectType::Blob) || !is_regular_file_mode(entry.filemode() as u32)
+        {
+            return TreeWalkResult::Ok;
+        }
+
+        if let Some(name) = entry.name() {
+            let relative_path = PathBuf::from(directory).join(name);
+            files.push(SourceFile::in_repository(
+                app_context.path.join(relative_path),
+                entry.id(),
+            ));
+        }
+
+        TreeWalkResult::Ok
+    })?;
+
+    Ok(files)
+}
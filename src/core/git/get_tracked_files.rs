@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use super::{GitError, canonical_workdir, is_regular_file_mode, open_repository};
+use crate::{context::AppContext, core::SourceFile};
+
+/// Lists the files recorded in the repository's index that live under the scan root.
+///
+/// Returned paths are rooted at `app_context.path`, the same way a directory walk would yield them.
+pub fn get_tracked_files(
+    app_context: &AppContext,
+    canonical_root: &Path,
+) -> Result<Vec<SourceFile>, GitError> {
+    let repository = open_repository(canonical_root)?;
+    let workdir = canonical_workdir(&repository)?;
+    let index = repository.index()?;
+
+    let files = index
+        .iter()
+        .filter(|entry| is_regular_file_mode(entry.mode))
+        .filter_map(|entry| {
+            let path = workdir.join(std::str::from_utf8(&entry.path).ok()?);
+            let relative_path = path.strip_prefix(canonical_root).ok()?;
+            Some(SourceFile::on_disk(app_context.path.join(relative_path)))
+        })
+        .collect();
+
+    Ok(files)
+}
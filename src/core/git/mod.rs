@@ -0,0 +1,112 @@
+mod get_author_composition;
+pub use get_author_composition::{AuthorEntry, get_author_composition};
+
+mod get_blame_line_counts;
+use get_blame_line_counts::get_blame_line_counts;
+
+mod get_changed_paths;
+pub use get_changed_paths::get_changed_paths;
+
+mod get_code_age;
+pub use get_code_age::{AgeBucket, CodeAgeEntry, get_code_age};
+
+mod get_composition_diff;
+pub use get_composition_diff::{CompositionDiffEntry, get_composition_diff};
+
+mod get_composition_history;
+pub use get_composition_history::{HistorySample, SampleStep, get_composition_history};
+
+mod get_excludes_file;
+pub use get_excludes_file::get_excludes_file;
+
+mod get_hotspots;
+pub use get_hotspots::{Hotspot, get_hotspots};
+
+mod get_revision_files;
+pub use get_revision_files::get_revision_files;
+
+mod get_tracked_files;
+pub use get_tracked_files::get_tracked_files;
+
+mod read_revision_file;
+pub use read_revision_file::read_revision_file;
+
+#[cfg(test)]
+pub mod test_repository;
+
+use git2::Repository;
+use std::path::{Path, PathBuf};
+
+use crate::context::AppContext;
+
+// index and tree entry modes that do not describe regular files
+const MODE_SYMLINK: u32 = 0o120000;
+const MODE_SUBMODULE: u32 = 0o160000;
+
+fn open_repository(path: &Path) -> Result<Repository, GitError> {
+    Repository::discover(path).map_err(|_| GitError::RepositoryNotFound(path.to_path_buf()))
+}
+
+// the canonical working directory of a non-bare repository
+fn canonical_workdir(repository: &Repository) -> Result<PathBuf, GitError> {
+    let workdir = repository.workdir().ok_or(GitError::BareRepository)?;
+    Ok(workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf()))
+}
+
+// the location of the scan root inside the repository, empty for the repository root
+fn repository_prefix(repository: &Repository, canonical_root: &Path) -> Result<PathBuf, GitError> {
+    let base = match repository.workdir() {
+        Some(_) => canonical_workdir(repository)?,
+        None => return Ok(PathBuf::new()),
+    };
+
+    Ok(canonical_root
+        .strip_prefix(&base)
+        .map(Path::to_path_buf)
+        .unwrap_or_default())
+}
+
+// the path of a file below the scan root, relative to the repository root
+fn repository_path(app_context: &AppContext, prefix: &Path, path: &Path) -> PathBuf {
+    let relative_path = path.strip_prefix(&app_context.path).unwrap_or(path);
+    prefix.join(relative_path)
+}
+
+fn is_regular_file_mode(mode: u32) -> bool {
+    mode != MODE_SYMLINK && mode != MODE_SUBMODULE
+}
+
+#[derive(Debug)]
+pub enum GitError {
+    RepositoryNotFound(PathBuf),
+    BareRepository,
+    RevisionNotFound(String),
+    OperationFailed(git2::Error),
+}
+
+impl From<git2::Error> for GitError {
+    fn from(error: git2::Error) -> Self {
+        GitError::OperationFailed(error)
+    }
+}
+
+impl std::error::Error for GitError {}
+
+impl std::fmt::Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitError::RepositoryNotFound(path) => {
+                write!(f, "no git repository found at '{}'", path.display())
+            }
+            GitError::BareRepository => write!(f, "bare repositories are not supported"),
+            GitError::RevisionNotFound(revision) => {
+                write!(f, "revision '{}' could not be resolved", revision)
+            }
+            GitError::OperationFailed(error) => {
+                write!(f, "git operation failed: {}", error.message())
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use super::open_repository;
+
+/// Reads the file at `repository_path`, relative to the repository root, as stored in
+/// `revision`. Returns `None` when the revision or the file does not exist.
+pub fn read_revision_file(
+    canonical_root: &Path,
+    revision: &str,
+    repository_path: &Path,
+) -> Option<Vec<u8>> {
+    let repository = open_repository(canonical_root).ok()?;
+    let tree = repository
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_tree())
+        .ok()?;
+    let blob = tree
+        .get_path(repository_path)
+        .and_then(|entry| repository.find_blob(entry.id()))
+        .ok()?;
+
+    Some(blob.content().to_vec())
+}
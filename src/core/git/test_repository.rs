@@ -0,0 +1,96 @@
+use chrono::NaiveDate;
+use clap::Parser;
+use git2::{IndexAddOption, Oid, Repository, Signature, Time};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::context::{AppContext, cli::Cli, config::Config};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A throwaway repository in the temp directory, removed again on drop.
+pub struct TestRepository {
+    pub root: PathBuf,
+    pub repository: Repository,
+}
+
+impl TestRepository {
+    pub fn new() -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let root =
+            std::env::temp_dir().join(format!("composition-test-{}-{}", std::process::id(), id));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("temp directory should be writable");
+
+        let root = root.canonicalize().expect("temp directory should exist");
+        let repository = Repository::init(&root).expect("repository should initialize");
+        TestRepository { root, repository }
+    }
+
+    pub fn write(&self, path: &str, content: &str) {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("parent directory should be writable");
+        }
+        fs::write(path, content).expect("file should be writable");
+    }
+
+    /// Commits every change in the working tree on top of HEAD as `author`, at noon UTC of
+    /// `date` (`YYYY-MM-DD`).
+    pub fn commit(&self, author: &str, date: &str) -> Oid {
+        let mut index = self.repository.index().expect("index should open");
+        index
+            .add_all(["*"], IndexAddOption::DEFAULT, None)
+            .expect("files should be added");
+        index
+            .update_all(["*"], None)
+            .expect("removed files should be dropped");
+        index.write().expect("index should be written");
+        let tree_id = index.write_tree().expect("tree should be written");
+        let tree = self
+            .repository
+            .find_tree(tree_id)
+            .expect("tree should exist");
+
+        let seconds = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .expect("date should be valid")
+            .and_hms_opt(12, 0, 0)
+            .expect("noon should be valid")
+            .and_utc()
+            .timestamp();
+        let email = format!("{}@example.com", author.to_lowercase());
+        let signature = Signature::new(author, &email, &Time::new(seconds, 0))
+            .expect("signature should be valid");
+
+        let parent = self
+            .repository
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        self.repository
+            .commit(Some("HEAD"), &signature, &signature, date, &tree, &parents)
+            .expect("commit should be created")
+    }
+
+    /// An app context for the repository root with the default config and `args` passed after
+    /// the path, e.g. `["diff", "HEAD~1"]`.
+    pub fn app_context(&self, args: &[&str]) -> AppContext {
+        let root = self.root.to_string_lossy().into_owned();
+        let cli = Cli::parse_from(
+            ["composition", root.as_str()]
+                .into_iter()
+                .chain(args.iter().copied()),
+        );
+        AppContext::with_config(cli, Config::default(), true)
+    }
+}
+
+impl Drop for TestRepository {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
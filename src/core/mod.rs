@@ -1,5 +1,34 @@
+mod classify_file;
+mod codeowners;
+mod detect_generated;
+mod detect_license;
+mod detect_packages;
+mod detect_tests;
+mod detect_vendored;
+
+pub mod git;
+
+mod source_file;
+use source_file::{ContentReader, ReadError, SourceFile, with_thread_repository};
+
 mod get_all_entries;
-pub use get_all_entries::get_all_entries;
+pub use get_all_entries::{filter_revision_entries, get_all_entries, get_revision_entries};
 
 mod get_composition;
-pub use get_composition::{CompositionEntry, get_composition};
+pub use get_composition::{
+    CompositionEntry, CountCache, DirectoryComposition, FileLineCount, SkippedFile, find_tracked,
+    get_composition, get_composition_cached, get_file_line_counts, group_file_line_counts,
+    is_counted_line, is_first_party,
+};
+
+mod get_category_composition;
+pub use get_category_composition::{CategoryComposition, get_category_composition};
+
+mod get_package_composition;
+pub use get_package_composition::{PackageComposition, get_package_composition};
+
+mod get_license_composition;
+pub use get_license_composition::{LicenseComposition, get_license_composition};
+
+mod get_owner_composition;
+pub use get_owner_composition::{OwnerComposition, get_owner_composition};
@@ -0,0 +1,93 @@
+use git2::{Oid, Repository};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// A file to be counted, either on disk or stored as a blob in the git object database.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub blob_id: Option<Oid>,
+}
+
+impl SourceFile {
+    pub fn on_disk(path: PathBuf) -> Self {
+        SourceFile {
+            path,
+            blob_id: None,
+        }
+    }
+
+    pub fn in_repository(path: PathBuf, blob_id: Oid) -> Self {
+        SourceFile {
+            path,
+            blob_id: Some(blob_id),
+        }
+    }
+}
+
+thread_local! {
+    // repository handles cannot be shared across threads, so each worker thread opens every
+    // repository once and keeps it for the rest of the run
+    static REPOSITORIES: RefCell<HashMap<PathBuf, Rc<Repository>>> = RefCell::new(HashMap::new());
+}
+
+/// Runs `f` with the calling thread's handle of the repository containing `path`, opening it on
+/// first use.
+pub fn with_thread_repository<T>(
+    path: &Path,
+    f: impl FnOnce(&Repository) -> T,
+) -> Result<T, git2::Error> {
+    let repository = REPOSITORIES.with(|repositories| -> Result<_, git2::Error> {
+        let mut repositories = repositories.borrow_mut();
+        match repositories.get(path) {
+            Some(repository) => Ok(Rc::clone(repository)),
+            None => {
+                let repository = Rc::new(Repository::discover(path)?);
+                repositories.insert(path.to_path_buf(), Rc::clone(&repository));
+                Ok(repository)
+            }
+        }
+    })?;
+
+    // the cache is no longer borrowed, so `f` may itself read through it
+    Ok(f(&repository))
+}
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    RepositoryUnavailable,
+}
+
+/// Reads the content of source files, from disk or through the calling thread's handle of the
+/// repository containing `root`.
+pub struct ContentReader {
+    root: PathBuf,
+}
+
+impl ContentReader {
+    pub fn new(root: &Path) -> Self {
+        ContentReader {
+            root: root.to_path_buf(),
+        }
+    }
+
+    pub fn read(&self, file: &SourceFile) -> Result<Vec<u8>, ReadError> {
+        let Some(blob_id) = file.blob_id else {
+            return fs::read(&file.path).map_err(ReadError::Io);
+        };
+
+        with_thread_repository(&self.root, |repository| {
+            let blob = repository
+                .find_blob(blob_id)
+                .map_err(|error| ReadError::Io(io::Error::other(error)))?;
+            Ok(blob.content().to_vec())
+        })
+        .map_err(|_| ReadError::RepositoryUnavailable)?
+    }
+}
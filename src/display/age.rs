@@ -0,0 +1,111 @@
+use colored::Colorize;
+use serde_json::json;
+
+use super::{bar, csv_field, digit_count};
+use crate::{
+    context::{AppContext, cli::OutputFormat},
+    core::git::CodeAgeEntry,
+};
+
+pub fn display_code_age(
+    app_context: &AppContext,
+    age_entries: &mut [CodeAgeEntry],
+    format: OutputFormat,
+) {
+    // reorder based on line count
+    age_entries.sort_by_key(|e| std::cmp::Reverse(e.line_count()));
+
+    match format {
+        OutputFormat::Table => print_histogram(app_context, age_entries),
+        OutputFormat::Csv => print_csv(age_entries),
+        OutputFormat::Json => print_json(age_entries),
+    }
+}
+
+fn print_histogram(app_context: &AppContext, age_entries: &[CodeAgeEntry]) {
+    if age_entries.is_empty() {
+        println!("No counted lines could be attributed");
+        return;
+    }
+
+    let max_label_width = age_entries
+        .iter()
+        .flat_map(|e| &e.line_counts)
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(4);
+
+    for entry in age_entries {
+        let total_lines = entry.line_count();
+        println!(
+            "{}",
+            format!("{} ({} lines)", entry.tracked.display, total_lines).bold()
+        );
+
+        let max_lines_width = entry
+            .line_counts
+            .iter()
+            .map(|(_, lines)| digit_count(*lines))
+            .max()
+            .unwrap_or(1);
+
+        // the cumulative share answers "how much of it has not been touched since then"
+        let mut cumulative = 0;
+        for (label, lines) in &entry.line_counts {
+            cumulative += lines;
+            let share = *lines as f32 / total_lines.max(1) as f32;
+            let cumulative_share = cumulative as f32 / total_lines.max(1) as f32;
+
+            println!(
+                "  {:<width_label$} | {:>width_lines$} lines | {:>6.2}% | {:>6.2}% cumulative | {}",
+                label,
+                lines,
+                share * 100.0,
+                cumulative_share * 100.0,
+                bar(app_context, &entry.tracked, share),
+                width_label = max_label_width,
+                width_lines = max_lines_width,
+            );
+        }
+    }
+}
+
+fn print_csv(age_entries: &[CodeAgeEntry]) {
+    println!("language,period,lines,percentage");
+    for entry in age_entries {
+        let total_lines = entry.line_count();
+        for (label, lines) in &entry.line_counts {
+            println!(
+                "{},{},{},{:.2}",
+                csv_field(&entry.tracked.display),
+                label,
+                lines,
+                (*lines as f32 / total_lines.max(1) as f32) * 100.0
+            );
+        }
+    }
+}
+
+fn print_json(age_entries: &[CodeAgeEntry]) {
+    let languages: Vec<serde_json::Value> = age_entries
+        .iter()
+        .map(|entry| {
+            let periods: Vec<serde_json::Value> = entry
+                .line_counts
+                .iter()
+                .map(|(label, lines)| json!({ "period": label, "lines": lines }))
+                .collect();
+
+            json!({
+                "language": entry.tracked.display,
+                "total_lines": entry.line_count(),
+                "periods": periods,
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&languages) {
+        Ok(output) => println!("{}", output),
+        Err(error) => eprintln!("Failed to serialize code age: {}", error),
+    }
+}
@@ -0,0 +1,139 @@
+use serde_json::json;
+
+use super::{MAX_TABLE_LANGUAGES, csv_field, digit_count};
+use crate::{context::cli::OutputFormat, core::git::AuthorEntry};
+
+pub fn display_author_composition(author_entries: &mut [AuthorEntry], format: OutputFormat) {
+    // reorder based on attributed line count
+    author_entries.sort_by(|a, b| {
+        b.line_count()
+            .cmp(&a.line_count())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    match format {
+        OutputFormat::Table => print_table(author_entries),
+        OutputFormat::Csv => print_csv(author_entries),
+        OutputFormat::Json => print_json(author_entries),
+    }
+}
+
+fn print_table(author_entries: &[AuthorEntry]) {
+    if author_entries.is_empty() {
+        println!("No counted lines could be attributed");
+        return;
+    }
+
+    // languages ordered by their total across all authors
+    let mut language_totals: Vec<(&str, usize)> = Vec::new();
+    for (tracked, lines) in author_entries.iter().flat_map(|e| &e.line_counts) {
+        match language_totals
+            .iter_mut()
+            .find(|(display, _)| *display == tracked.display)
+        {
+            Some((_, total)) => *total += lines,
+            None => language_totals.push((&tracked.display, *lines)),
+        }
+    }
+    language_totals.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+
+    let (shown, folded) = language_totals.split_at(language_totals.len().min(MAX_TABLE_LANGUAGES));
+    let mut headers: Vec<&str> = shown.iter().map(|(display, _)| *display).collect();
+    if !folded.is_empty() {
+        headers.push("Other");
+    }
+
+    let total_lines: usize = author_entries.iter().map(|e| e.line_count()).sum();
+    let max_name_width = author_entries
+        .iter()
+        .map(|e| e.name.len())
+        .max()
+        .unwrap_or(6)
+        .max(6);
+    let lines_width = digit_count(total_lines).max(5);
+    let widths: Vec<usize> = headers.iter().map(|h| h.len().max(lines_width)).collect();
+
+    let header: String = headers
+        .iter()
+        .zip(&widths)
+        .map(|(h, w)| format!(" | {:>w$}", h, w = w))
+        .collect();
+    println!(
+        "{:<name_width$} | {:>lines_width$} | {:>6}{}",
+        "author",
+        "lines",
+        "share",
+        header,
+        name_width = max_name_width,
+    );
+
+    for entry in author_entries {
+        let lines_of = |display: &str| {
+            entry
+                .line_counts
+                .iter()
+                .find(|(tracked, _)| tracked.display == display)
+                .map_or(0, |(_, lines)| *lines)
+        };
+
+        let mut values: Vec<usize> = shown.iter().map(|(display, _)| lines_of(display)).collect();
+        if !folded.is_empty() {
+            values.push(folded.iter().map(|(display, _)| lines_of(display)).sum());
+        }
+
+        let row: String = values
+            .iter()
+            .zip(&widths)
+            .map(|(v, w)| format!(" | {:>w$}", v, w = w))
+            .collect();
+        let share = (entry.line_count() as f32 / total_lines.max(1) as f32) * 100.0;
+        println!(
+            "{:<name_width$} | {:>lines_width$} | {:>5.2}%{}",
+            entry.name,
+            entry.line_count(),
+            share,
+            row,
+            name_width = max_name_width,
+        );
+    }
+}
+
+fn print_csv(author_entries: &[AuthorEntry]) {
+    println!("author,email,language,lines");
+    for entry in author_entries {
+        for (tracked, lines) in &entry.line_counts {
+            println!(
+                "{},{},{},{}",
+                csv_field(&entry.name),
+                csv_field(&entry.email),
+                csv_field(&tracked.display),
+                lines
+            );
+        }
+    }
+}
+
+fn print_json(author_entries: &[AuthorEntry]) {
+    let authors: Vec<serde_json::Value> = author_entries
+        .iter()
+        .map(|entry| {
+            let languages: Vec<serde_json::Value> = entry
+                .line_counts
+                .iter()
+                .map(|(tracked, lines)| json!({ "language": tracked.display, "lines": lines }))
+                .collect();
+
+            json!({
+                "author": entry.name,
+                "email": entry.email,
+                "total_lines": entry.line_count(),
+                "languages": languages,
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&authors) {
+        Ok(output) => println!("{}", output),
+        Err(error) => eprintln!("Failed to serialize authors: {}", error),
+    }
+}
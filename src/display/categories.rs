@@ -0,0 +1,128 @@
+use super::digit_count;
+use crate::{context::AppContext, core::CategoryComposition};
+
+pub fn display_category_composition(
+    app_context: &AppContext,
+    categories: &mut [CategoryComposition],
+) {
+    if categories.is_empty() {
+        println!("No counted lines found");
+        return;
+    }
+
+    for row in category_rows(app_context, categories) {
+        println!("{}", row);
+    }
+}
+
+// one row per category, largest first, with the languages of each category
+fn category_rows(app_context: &AppContext, categories: &mut [CategoryComposition]) -> Vec<String> {
+    // reorder based on line count
+    categories.sort_by_key(|c| std::cmp::Reverse(c.line_count()));
+
+    // excluded categories are still listed, but do not take a share
+    let excluded = &app_context.config.percentage_excluded_categories;
+    let total_lines: usize = categories
+        .iter()
+        .filter(|c| !excluded.contains(&c.category))
+        .map(|c| c.line_count())
+        .sum();
+
+    let max_category_width = categories
+        .iter()
+        .map(|c| c.category.as_str().len())
+        .max()
+        .unwrap_or(0);
+    let max_lines_width = categories
+        .iter()
+        .map(|c| digit_count(c.line_count()))
+        .max()
+        .unwrap_or(1);
+
+    let mut rows = Vec::with_capacity(categories.len());
+    for category in categories.iter_mut() {
+        let share = if excluded.contains(&category.category) {
+            format!("{:>6}", "-")
+        } else {
+            let percentage = (category.line_count() as f32 / total_lines.max(1) as f32) * 100.0;
+            format!("{:>5.2}%", percentage)
+        };
+
+        // languages of the category, largest first
+        category
+            .entries
+            .sort_by_key(|e| std::cmp::Reverse(e.line_count));
+        let languages: Vec<String> = category
+            .entries
+            .iter()
+            .map(|e| format!("{} {}", e.tracked.display, e.line_count))
+            .collect();
+
+        rows.push(format!(
+            "{:>width_category$} | {:>width_lines$} lines | {} | {}",
+            category.category.as_str(),
+            category.line_count(),
+            share,
+            languages.join(", "),
+            width_category = max_category_width + 1,
+            width_lines = max_lines_width,
+        ));
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod categories_tests {
+    use super::*;
+    use crate::{
+        context::config::Category,
+        core::{
+            get_all_entries, get_category_composition, get_composition,
+            git::test_repository::TestRepository,
+        },
+    };
+
+    fn rows(repository: &TestRepository, excluded: Vec<Category>) -> Vec<String> {
+        let mut app_context = repository.app_context(&["--by-category"]);
+        app_context.config.percentage_excluded_categories = excluded;
+        let files = get_all_entries(&app_context).unwrap();
+        let composition = get_composition(&app_context, files);
+        let mut categories = get_category_composition(&composition.entries);
+
+        category_rows(&app_context, &mut categories)
+    }
+
+    fn sample() -> TestRepository {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\nfn two() {}\nfn three() {}\n");
+        repository.write("main.py", "one = 1\n");
+        repository.write("README.md", "Some prose.\n");
+        repository.write("data.json", "\"one\": 1,\n\"two\": 2,\n\"three\": 3\n");
+        repository
+    }
+
+    #[test]
+    fn categories_are_listed_largest_first_with_their_languages() {
+        assert_eq!(
+            rows(&sample(), vec![]),
+            [
+                " programming | 4 lines | 50.00% | Rust 3, Python 1",
+                "        data | 3 lines | 37.50% | JSON 3",
+                "       prose | 1 lines | 12.50% | Markdown 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn excluded_categories_are_left_out_of_the_percentage_base() {
+        assert_eq!(
+            rows(&sample(), vec![Category::Data, Category::Prose]),
+            [
+                " programming | 4 lines | 100.00% | Rust 3, Python 1",
+                "        data | 3 lines |      - | JSON 3",
+                "       prose | 1 lines |      - | Markdown 1",
+            ]
+        );
+    }
+}
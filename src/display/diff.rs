@@ -0,0 +1,72 @@
+use colored::Colorize;
+
+use crate::{context::AppContext, core::git::CompositionDiffEntry};
+
+pub fn display_composition_diff(
+    app_context: &AppContext,
+    diff_entries: &mut Vec<CompositionDiffEntry>,
+) {
+    if diff_entries.is_empty() {
+        println!("No counted lines changed");
+        return;
+    }
+
+    // reorder based on the size of the net change
+    diff_entries.sort_by_key(|e| std::cmp::Reverse(e.net().unsigned_abs()));
+
+    let max_display_width = diff_entries
+        .iter()
+        .map(|e| e.tracked.display.len())
+        .max()
+        .unwrap_or(20);
+
+    let max_added_width = diff_entries
+        .iter()
+        .map(|e| format!("+{}", e.added).len())
+        .max()
+        .unwrap_or(1);
+
+    let max_removed_width = diff_entries
+        .iter()
+        .map(|e| format!("-{}", e.removed).len())
+        .max()
+        .unwrap_or(1);
+
+    let max_net_width = diff_entries
+        .iter()
+        .map(|e| format!("{:+}", e.net()).len())
+        .max()
+        .unwrap_or(1);
+
+    for entry in diff_entries {
+        let added = format!(
+            "{:>width$}",
+            format!("+{}", entry.added),
+            width = max_added_width
+        );
+        let removed = format!(
+            "{:>width$}",
+            format!("-{}", entry.removed),
+            width = max_removed_width
+        );
+
+        let (added, removed) = if app_context.config.use_color {
+            (added.green(), removed.red())
+        } else {
+            (added.normal(), removed.normal())
+        };
+
+        println!(
+            "{:>width_display$} | {} | {} | {:>width_net$} net | {:>6.2}% -> {:>6.2}% | {:>+6.2} pp",
+            entry.tracked.display,
+            added,
+            removed,
+            format!("{:+}", entry.net()),
+            entry.base_percentage,
+            entry.head_percentage,
+            entry.percentage_point_shift(),
+            width_display = max_display_width + 1,
+            width_net = max_net_width,
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use super::{digit_count, relative_path};
+use crate::{
+    context::{AppContext, cli::FileOrder},
+    core::{CompositionEntry, FileLineCount},
+};
+
+pub fn display_file_line_counts(
+    app_context: &AppContext,
+    files: &[FileLineCount],
+    composition_entries: &[CompositionEntry],
+) {
+    if files.is_empty() {
+        println!("No counted files found");
+        return;
+    }
+
+    let rows = file_rows(app_context, files, composition_entries);
+    let shown = &rows[..rows.len().min(app_context.top_files.unwrap_or(usize::MAX))];
+
+    let max_lines_width = shown
+        .iter()
+        .map(|(file, _, _)| digit_count(file.line_count))
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let max_display_width = shown
+        .iter()
+        .map(|(file, _, _)| file.tracked.display.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+
+    println!(
+        "{:>width_lines$} | {:>7} | {:<width_display$} | path",
+        "lines",
+        "share",
+        "language",
+        width_lines = max_lines_width,
+        width_display = max_display_width,
+    );
+    for (file, share, path) in shown {
+        println!(
+            "{:>width_lines$} | {:>6.2}% | {:<width_display$} | {}",
+            file.line_count,
+            share,
+            file.tracked.display,
+            path,
+            width_lines = max_lines_width,
+            width_display = max_display_width,
+        );
+    }
+
+    if shown.len() < rows.len() {
+        println!("{} of {} files shown", shown.len(), rows.len());
+    }
+}
+
+// each file with its share of the language total and its displayed path, in the requested order
+fn file_rows<'a>(
+    app_context: &AppContext,
+    files: &'a [FileLineCount],
+    composition_entries: &[CompositionEntry],
+) -> Vec<(&'a FileLineCount, f32, String)> {
+    let language_totals: HashMap<&str, usize> = composition_entries
+        .iter()
+        .map(|e| (e.tracked.display.as_str(), e.line_count))
+        .collect();
+    let share = |file: &FileLineCount| {
+        let total = language_totals
+            .get(file.tracked.display.as_str())
+            .copied()
+            .unwrap_or(0);
+        (file.line_count as f32 / total.max(1) as f32) * 100.0
+    };
+
+    // generated and vendored files are kept for the group tables, but only hand-written lines
+    // are listed; every order falls back to the path, so the listing is stable
+    let mut rows: Vec<(&FileLineCount, f32, String)> = files
+        .iter()
+        .filter(|file| file.line_count > 0)
+        .map(|file| (file, share(file), relative_path(app_context, &file.path)))
+        .collect();
+    rows.sort_by(|(a, a_share, a_path), (b, b_share, b_path)| {
+        let order = match app_context.file_order {
+            FileOrder::Lines => b.line_count.cmp(&a.line_count),
+            FileOrder::Share => b_share.total_cmp(a_share),
+            FileOrder::Language => a
+                .tracked
+                .display
+                .cmp(&b.tracked.display)
+                .then_with(|| b.line_count.cmp(&a.line_count)),
+            FileOrder::Path => std::cmp::Ordering::Equal,
+        };
+        order.then_with(|| a_path.cmp(b_path))
+    });
+
+    rows
+}
+
+#[cfg(test)]
+mod files_tests {
+    use super::*;
+    use crate::core::{get_all_entries, get_composition, git::test_repository::TestRepository};
+
+    fn listed_files(repository: &TestRepository, sort: &str) -> Vec<(String, u32)> {
+        let app_context = repository.app_context(&["--files", &format!("--sort={sort}")]);
+        let files = get_all_entries(&app_context).unwrap();
+        let composition = get_composition(&app_context, files);
+
+        file_rows(&app_context, &composition.files, &composition.entries)
+            .into_iter()
+            .map(|(_, share, path)| (path, share.round() as u32))
+            .collect()
+    }
+
+    fn sample() -> TestRepository {
+        let repository = TestRepository::new();
+        repository.write("a.py", "one = 1\ntwo = 2\nthree = 3\n");
+        repository.write("b.py", "four = 4\n");
+        repository.write(
+            "main.rs",
+            "fn one() {}\nfn two() {}\nfn three() {}\nfn four() {}\n",
+        );
+        repository.write("lib.rs", "fn five() {}\n");
+        repository
+    }
+
+    fn paths(rows: &[(String, u32)]) -> Vec<&str> {
+        rows.iter().map(|(path, _)| path.as_str()).collect()
+    }
+
+    #[test]
+    fn shares_are_relative_to_the_language_total() {
+        let repository = sample();
+
+        assert_eq!(
+            listed_files(&repository, "share"),
+            [
+                ("main.rs".to_string(), 80),
+                ("a.py".to_string(), 75),
+                ("b.py".to_string(), 25),
+                ("lib.rs".to_string(), 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_order_falls_back_to_the_path() {
+        let repository = sample();
+
+        assert_eq!(
+            paths(&listed_files(&repository, "lines")),
+            ["main.rs", "a.py", "b.py", "lib.rs"]
+        );
+        assert_eq!(
+            paths(&listed_files(&repository, "language")),
+            ["a.py", "b.py", "main.rs", "lib.rs"]
+        );
+        assert_eq!(
+            paths(&listed_files(&repository, "path")),
+            ["a.py", "b.py", "lib.rs", "main.rs"]
+        );
+    }
+}
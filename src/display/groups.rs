@@ -0,0 +1,217 @@
+use colored::Colorize;
+
+use super::{LineCountFn, digit_count};
+use crate::{
+    context::AppContext,
+    core::{
+        CompositionEntry, DirectoryComposition, LicenseComposition, OwnerComposition,
+        PackageComposition,
+    },
+};
+
+pub fn display_directory_composition(
+    app_context: &AppContext,
+    directories: &[DirectoryComposition],
+    composition_entries: &[CompositionEntry],
+) {
+    let groups: Vec<(String, &[CompositionEntry])> = directories
+        .iter()
+        .map(|directory| {
+            let label = if directory.path.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                directory.path.display().to_string()
+            };
+            (label, directory.entries.as_slice())
+        })
+        .collect();
+
+    print_group_table(app_context, "directory", &groups, composition_entries);
+}
+
+pub fn display_package_composition(
+    app_context: &AppContext,
+    packages: &[PackageComposition],
+    composition_entries: &[CompositionEntry],
+) {
+    let groups: Vec<(String, &[CompositionEntry])> = packages
+        .iter()
+        .map(|package| {
+            let label = match &package.name {
+                Some(name) if package.root.as_os_str().is_empty() => format!("{} (.)", name),
+                Some(name) => format!("{} ({})", name, package.root.display()),
+                None => "(no package)".to_string(),
+            };
+            (label, package.entries.as_slice())
+        })
+        .collect();
+
+    print_group_table(app_context, "package", &groups, composition_entries);
+}
+
+pub fn display_owner_composition(
+    app_context: &AppContext,
+    owners: &[OwnerComposition],
+    composition_entries: &[CompositionEntry],
+) {
+    let groups: Vec<(String, &[CompositionEntry])> = owners
+        .iter()
+        .map(|owner| {
+            let label = owner.owner.as_deref().unwrap_or("(unowned)").to_string();
+            (label, owner.entries.as_slice())
+        })
+        .collect();
+
+    print_group_table(app_context, "owner", &groups, composition_entries);
+
+    // unowned code is called out below the table
+    let total_lines: usize = composition_entries.iter().map(|e| e.line_count).sum();
+    let unowned_lines: usize = owners
+        .iter()
+        .filter(|owner| owner.owner.is_none())
+        .flat_map(|owner| &owner.entries)
+        .map(|e| e.line_count)
+        .sum();
+    if unowned_lines > 0 {
+        let percentage = (unowned_lines as f32 / total_lines.max(1) as f32) * 100.0;
+        println!(
+            "{}",
+            format!(
+                "{} lines ({:.2}%) have no owner in CODEOWNERS",
+                unowned_lines, percentage
+            )
+            .yellow()
+        );
+    }
+}
+
+pub fn display_license_composition(
+    app_context: &AppContext,
+    licenses: &[LicenseComposition],
+    composition_entries: &[CompositionEntry],
+) {
+    let groups: Vec<(String, &[CompositionEntry])> = licenses
+        .iter()
+        .map(|license| {
+            let label = license
+                .license
+                .as_deref()
+                .unwrap_or("(unlicensed)")
+                .to_string();
+            (label, license.entries.as_slice())
+        })
+        .collect();
+
+    print_group_table(app_context, "license", &groups, composition_entries);
+}
+
+// one row per group and one column per language, followed by the totals
+fn print_group_table(
+    app_context: &AppContext,
+    group_header: &str,
+    groups: &[(String, &[CompositionEntry])],
+    composition_entries: &[CompositionEntry],
+) {
+    let total_lines: usize = composition_entries.iter().map(|e| e.line_count).sum();
+    if total_lines == 0 {
+        println!("No counted lines found");
+        return;
+    }
+
+    // languages below the threshold share a single column
+    let mut languages: Vec<&CompositionEntry> = composition_entries
+        .iter()
+        .filter(|e| e.line_count > 0)
+        .collect();
+    languages.sort_by_key(|e| std::cmp::Reverse(e.line_count));
+    let shown_count = languages
+        .iter()
+        .take_while(|e| {
+            (e.line_count as f32 / total_lines as f32) * 100.0 >= app_context.other_threshold
+        })
+        .count();
+    let (shown, folded) = languages.split_at(shown_count);
+
+    let mut headers: Vec<&str> = shown.iter().map(|e| e.tracked.display.as_str()).collect();
+    if !folded.is_empty() {
+        headers.push("Other");
+    }
+    headers.push("total");
+    let total_index = headers.len() - 1;
+
+    // generated and vendored lines follow the total they are not part of, once there are any
+    let buckets: Vec<(&str, LineCountFn)> = [
+        ("generated", (|e| e.generated_line_count) as LineCountFn),
+        ("vendored", |e| e.vendored_line_count),
+    ]
+    .into_iter()
+    .filter(|(_, value)| composition_entries.iter().any(|e| value(e) > 0))
+    .collect();
+    headers.extend(buckets.iter().map(|(label, _)| *label));
+
+    // one row of cells per group, in the same order as the headers
+    let row_cells = |entries: &[CompositionEntry]| -> Vec<usize> {
+        let lines_of = |display: &str| {
+            entries
+                .iter()
+                .find(|e| e.tracked.display == display)
+                .map_or(0, |e| e.line_count)
+        };
+
+        let mut cells: Vec<usize> = shown.iter().map(|e| lines_of(&e.tracked.display)).collect();
+        if !folded.is_empty() {
+            cells.push(folded.iter().map(|e| lines_of(&e.tracked.display)).sum());
+        }
+        cells.push(entries.iter().map(|e| e.line_count).sum());
+        cells.extend(
+            buckets
+                .iter()
+                .map(|(_, value)| entries.iter().map(value).sum::<usize>()),
+        );
+        cells
+    };
+
+    let mut rows: Vec<(String, Vec<usize>)> = groups
+        .iter()
+        .map(|(label, entries)| (label.clone(), row_cells(entries)))
+        .filter(|(_, cells)| cells.iter().any(|cell| *cell > 0))
+        .collect();
+    rows.sort_by(|(a_label, a), (b_label, b)| {
+        b[total_index]
+            .cmp(&a[total_index])
+            .then(a_label.cmp(b_label))
+    });
+
+    let totals = row_cells(composition_entries);
+
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0)
+        .max(group_header.len());
+    let widths: Vec<usize> = headers
+        .iter()
+        .zip(&totals)
+        .map(|(header, total)| header.len().max(digit_count(*total)))
+        .collect();
+
+    let format_row = |label: &str, cells: &[String]| -> String {
+        let cells: String = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" | {:>width$}", cell, width = width))
+            .collect();
+        format!("{:<width$}{}", label, cells, width = label_width)
+    };
+
+    let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    println!("{}", format_row(group_header, &headers).bold());
+    for (label, cells) in &rows {
+        let cells: Vec<String> = cells.iter().map(|c| c.to_string()).collect();
+        println!("{}", format_row(label, &cells));
+    }
+
+    let totals: Vec<String> = totals.iter().map(|c| c.to_string()).collect();
+    println!("{}", format_row("total", &totals).bold());
+}
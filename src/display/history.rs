@@ -0,0 +1,269 @@
+use std::{fmt::Write as _, fs, io, path::Path};
+
+use serde_json::json;
+
+use super::{MAX_TABLE_LANGUAGES, csv_field};
+use crate::{
+    context::{cli::OutputFormat, config::Tracked},
+    core::git::HistorySample,
+};
+
+// used for languages without a configured color in the SVG chart
+const FALLBACK_COLORS: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#9c755f",
+];
+
+pub fn display_composition_history(samples: &[HistorySample], format: OutputFormat) {
+    match format {
+        OutputFormat::Table => print_table(samples),
+        OutputFormat::Csv => print_csv(samples),
+        OutputFormat::Json => print_json(samples),
+    }
+}
+
+fn print_table(samples: &[HistorySample]) {
+    if samples.is_empty() {
+        println!("No commits found in the sampled range");
+        return;
+    }
+
+    let languages = history_languages(samples);
+    let (shown, folded) = languages.split_at(languages.len().min(MAX_TABLE_LANGUAGES));
+
+    let mut headers: Vec<&str> = shown.iter().map(|t| t.display.as_str()).collect();
+    if !folded.is_empty() {
+        headers.push("Other");
+    }
+    let widths: Vec<usize> = headers.iter().map(|h| h.len().max(7)).collect();
+
+    let header: String = headers
+        .iter()
+        .zip(&widths)
+        .map(|(h, w)| format!(" {:>w$} |", h, w = w))
+        .collect();
+    let header = format!("{:<10} | {:<7} |{}", "date", "commit", header);
+    println!("{}", header.trim_end_matches('|').trim_end());
+
+    for sample in samples {
+        let mut shares: Vec<f32> = shown.iter().map(|t| share(sample, t)).collect();
+        if !folded.is_empty() {
+            shares.push(folded.iter().map(|t| share(sample, t)).sum());
+        }
+
+        let row: String = shares
+            .iter()
+            .zip(&widths)
+            .map(|(s, w)| format!(" {:>w$} |", format!("{:.2}%", s), w = w))
+            .collect();
+        let row = format!(
+            "{} | {:.7} |{}",
+            sample.date,
+            sample.commit_id.to_string(),
+            row
+        );
+        println!("{}", row.trim_end_matches('|').trim_end());
+    }
+}
+
+fn print_csv(samples: &[HistorySample]) {
+    println!("date,commit,language,lines,percentage");
+    for sample in samples {
+        for entry in &sample.entries {
+            println!(
+                "{},{},{},{},{:.2}",
+                sample.date,
+                sample.commit_id,
+                csv_field(&entry.tracked.display),
+                entry.line_count,
+                entry.percentage
+            );
+        }
+    }
+}
+
+fn print_json(samples: &[HistorySample]) {
+    let samples: Vec<serde_json::Value> = samples
+        .iter()
+        .map(|sample| {
+            let languages: Vec<serde_json::Value> = sample
+                .entries
+                .iter()
+                .map(|entry| {
+                    json!({
+                        "language": entry.tracked.display,
+                        "lines": entry.line_count,
+                        "percentage": (f64::from(entry.percentage) * 100.0).round() / 100.0,
+                    })
+                })
+                .collect();
+
+            json!({
+                "date": sample.date.to_string(),
+                "commit": sample.commit_id.to_string(),
+                "total_lines": sample.entries.iter().map(|e| e.line_count).sum::<usize>(),
+                "languages": languages,
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&samples) {
+        Ok(output) => println!("{}", output),
+        Err(error) => eprintln!("Failed to serialize history: {}", error),
+    }
+}
+
+/// Writes a stacked area chart of the language shares over time.
+pub fn write_history_svg(samples: &[HistorySample], path: &Path) -> io::Result<()> {
+    const WIDTH: f32 = 800.0;
+    const HEIGHT: f32 = 400.0;
+    const MARGIN: f32 = 40.0;
+    const LEGEND_WIDTH: f32 = 160.0;
+
+    let plot_width = WIDTH - 2.0 * MARGIN - LEGEND_WIDTH;
+    let plot_height = HEIGHT - 2.0 * MARGIN;
+    let languages = history_languages(samples);
+
+    // x positions follow the sample dates, so the final sample does not distort the spacing
+    let first_date = samples.first().map(|s| s.date);
+    let last_date = samples.last().map(|s| s.date);
+    let span = match (first_date, last_date) {
+        (Some(first), Some(last)) => (last - first).num_days().max(1) as f32,
+        _ => 1.0,
+    };
+    let xs: Vec<f32> = samples
+        .iter()
+        .map(|s| {
+            let days = first_date.map_or(0, |first| (s.date - first).num_days()) as f32;
+            MARGIN + days / span * plot_width
+        })
+        .collect();
+    let y = |share: f32| MARGIN + plot_height - share / 100.0 * plot_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{MARGIN}" y="{MARGIN}" width="{plot_width}" height="{plot_height}" fill="#f5f5f5"/>"##
+    );
+
+    let mut lower = vec![0.0_f32; samples.len()];
+    for (index, tracked) in languages.iter().enumerate() {
+        let upper: Vec<f32> = samples
+            .iter()
+            .zip(&lower)
+            .map(|(sample, base)| base + share(sample, tracked))
+            .collect();
+
+        let mut points: Vec<String> = xs
+            .iter()
+            .zip(&upper)
+            .map(|(x, share)| format!("{:.1},{:.1}", x, y(*share)))
+            .collect();
+        points.extend(
+            xs.iter()
+                .zip(&lower)
+                .rev()
+                .map(|(x, share)| format!("{:.1},{:.1}", x, y(*share))),
+        );
+
+        let color = series_color(tracked, index);
+        let _ = writeln!(
+            svg,
+            r#"<polygon points="{}" fill="{}"><title>{}</title></polygon>"#,
+            points.join(" "),
+            color,
+            xml_escape(&tracked.display)
+        );
+
+        let legend_y = MARGIN + index as f32 * 18.0;
+        let legend_x = WIDTH - LEGEND_WIDTH;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{legend_x}" y="{legend_y}" width="12" height="12" fill="{color}"/><text x="{}" y="{}">{}</text>"#,
+            legend_x + 18.0,
+            legend_y + 10.0,
+            xml_escape(&tracked.display)
+        );
+
+        lower = upper;
+    }
+
+    // axis labels
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="end">100%</text><text x="{}" y="{}" text-anchor="end">0%</text>"#,
+        MARGIN - 4.0,
+        MARGIN + 4.0,
+        MARGIN - 4.0,
+        MARGIN + plot_height + 4.0
+    );
+    if let (Some(first), Some(last)) = (first_date, last_date) {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{MARGIN}" y="{}">{first}</text><text x="{}" y="{}" text-anchor="end">{last}</text>"#,
+            HEIGHT - MARGIN + 16.0,
+            MARGIN + plot_width,
+            HEIGHT - MARGIN + 16.0
+        );
+    }
+    svg.push_str("</svg>\n");
+
+    fs::write(path, svg)
+}
+
+// every language found in any sample, ordered by its share in the latest sample, then its peak share
+fn history_languages(samples: &[HistorySample]) -> Vec<&Tracked> {
+    let mut languages: Vec<&Tracked> = Vec::new();
+    for sample in samples {
+        for entry in &sample.entries {
+            if !languages.iter().any(|t| t.display == entry.tracked.display) {
+                languages.push(&entry.tracked);
+            }
+        }
+    }
+
+    let latest = samples.last();
+    let peak = |tracked: &Tracked| {
+        samples
+            .iter()
+            .map(|s| share(s, tracked))
+            .fold(0_f32, f32::max)
+    };
+    languages.sort_by(|a, b| {
+        let latest_a = latest.map_or(0.0, |s| share(s, a));
+        let latest_b = latest.map_or(0.0, |s| share(s, b));
+        latest_b
+            .total_cmp(&latest_a)
+            .then_with(|| peak(b).total_cmp(&peak(a)))
+    });
+
+    languages
+}
+
+fn share(sample: &HistorySample, tracked: &Tracked) -> f32 {
+    sample
+        .entries
+        .iter()
+        .find(|e| e.tracked.display == tracked.display)
+        .map_or(0.0, |e| e.percentage)
+}
+
+fn series_color(tracked: &Tracked, index: usize) -> String {
+    match &tracked.color {
+        Some(hex) if super::hex_to_rgb(hex).is_some() => {
+            format!("#{}", hex.trim_start_matches('#'))
+        }
+        _ => FALLBACK_COLORS[index % FALLBACK_COLORS.len()].to_string(),
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
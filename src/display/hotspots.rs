@@ -0,0 +1,206 @@
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    path::{Component, Path},
+};
+
+use super::{csv_field, relative_path};
+use crate::{
+    context::{AppContext, cli::OutputFormat},
+    core::git::Hotspot,
+};
+
+#[derive(Default)]
+struct Rollup {
+    files: usize,
+    line_count: usize,
+    commit_count: usize,
+    lines_changed: usize,
+}
+
+pub fn display_hotspots(
+    app_context: &AppContext,
+    hotspots: &mut [Hotspot],
+    top: usize,
+    format: OutputFormat,
+) {
+    // reorder based on score, ties broken by how much the file changed
+    hotspots.sort_by(|a, b| {
+        b.score()
+            .cmp(&a.score())
+            .then_with(|| b.lines_changed.cmp(&a.lines_changed))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    // rollups cover every changed file, not only the listed ones
+    let languages = rollup(hotspots, |hotspot| hotspot.tracked.display.clone());
+    let directories = rollup(hotspots, |hotspot| {
+        top_level_directory(app_context, &hotspot.path)
+    });
+    let hotspots = &hotspots[..hotspots.len().min(top)];
+
+    match format {
+        OutputFormat::Table => {
+            if hotspots.is_empty() {
+                println!("No counted files changed in the window");
+                return;
+            }
+
+            print_files(app_context, hotspots);
+            println!();
+            print_rollup("language", &languages);
+            println!();
+            print_rollup("directory", &directories);
+        }
+        OutputFormat::Csv => {
+            println!("path,language,lines,commits,lines_changed,score");
+            for hotspot in hotspots {
+                println!(
+                    "{},{},{},{},{},{}",
+                    csv_field(&relative_path(app_context, &hotspot.path)),
+                    csv_field(&hotspot.tracked.display),
+                    hotspot.line_count,
+                    hotspot.commit_count,
+                    hotspot.lines_changed,
+                    hotspot.score()
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let files: Vec<serde_json::Value> = hotspots
+                .iter()
+                .map(|hotspot| {
+                    json!({
+                        "path": relative_path(app_context, &hotspot.path),
+                        "language": hotspot.tracked.display,
+                        "lines": hotspot.line_count,
+                        "commits": hotspot.commit_count,
+                        "lines_changed": hotspot.lines_changed,
+                        "score": hotspot.score(),
+                    })
+                })
+                .collect();
+
+            let output = json!({
+                "files": files,
+                "languages": rollup_json("language", &languages),
+                "directories": rollup_json("directory", &directories),
+            });
+
+            match serde_json::to_string_pretty(&output) {
+                Ok(output) => println!("{}", output),
+                Err(error) => eprintln!("Failed to serialize hotspots: {}", error),
+            }
+        }
+    }
+}
+
+fn print_files(app_context: &AppContext, hotspots: &[Hotspot]) {
+    let paths: Vec<String> = hotspots
+        .iter()
+        .map(|hotspot| relative_path(app_context, &hotspot.path))
+        .collect();
+    let max_display_width = hotspots
+        .iter()
+        .map(|e| e.tracked.display.len())
+        .max()
+        .unwrap_or(8)
+        .max(8);
+
+    println!(
+        "{:>10} | {:>8} | {:>7} | {:>8} | {:<width_display$} | path",
+        "score",
+        "lines",
+        "commits",
+        "changed",
+        "language",
+        width_display = max_display_width,
+    );
+    for (hotspot, path) in hotspots.iter().zip(&paths) {
+        println!(
+            "{:>10} | {:>8} | {:>7} | {:>8} | {:<width_display$} | {}",
+            hotspot.score(),
+            hotspot.line_count,
+            hotspot.commit_count,
+            hotspot.lines_changed,
+            hotspot.tracked.display,
+            path,
+            width_display = max_display_width,
+        );
+    }
+}
+
+fn print_rollup(label: &str, rollups: &[(String, Rollup)]) {
+    let max_key_width = rollups
+        .iter()
+        .map(|(key, _)| key.len())
+        .max()
+        .unwrap_or(0)
+        .max(label.len());
+
+    println!(
+        "{:<width_key$} | {:>5} | {:>8} | {:>7} | {:>8}",
+        label,
+        "files",
+        "lines",
+        "changes",
+        "changed",
+        width_key = max_key_width,
+    );
+    for (key, rollup) in rollups {
+        println!(
+            "{:<width_key$} | {:>5} | {:>8} | {:>7} | {:>8}",
+            key,
+            rollup.files,
+            rollup.line_count,
+            rollup.commit_count,
+            rollup.lines_changed,
+            width_key = max_key_width,
+        );
+    }
+}
+
+fn rollup_json(label: &str, rollups: &[(String, Rollup)]) -> Vec<serde_json::Value> {
+    rollups
+        .iter()
+        .map(|(key, rollup)| {
+            json!({
+                label: key,
+                "files": rollup.files,
+                "lines": rollup.line_count,
+                "changes": rollup.commit_count,
+                "lines_changed": rollup.lines_changed,
+            })
+        })
+        .collect()
+}
+
+// totals per key, ordered by the lines changed
+fn rollup(hotspots: &[Hotspot], key: impl Fn(&Hotspot) -> String) -> Vec<(String, Rollup)> {
+    let mut rollups: HashMap<String, Rollup> = HashMap::new();
+    for hotspot in hotspots {
+        let rollup = rollups.entry(key(hotspot)).or_default();
+        rollup.files += 1;
+        rollup.line_count += hotspot.line_count;
+        rollup.commit_count += hotspot.commit_count;
+        rollup.lines_changed += hotspot.lines_changed;
+    }
+
+    let mut rollups: Vec<(String, Rollup)> = rollups.into_iter().collect();
+    rollups.sort_by(|(a_key, a), (b_key, b)| {
+        b.lines_changed
+            .cmp(&a.lines_changed)
+            .then_with(|| a_key.cmp(b_key))
+    });
+    rollups
+}
+
+// files directly in the scanned directory are grouped under "."
+fn top_level_directory(app_context: &AppContext, path: &Path) -> String {
+    let relative_path = path.strip_prefix(&app_context.path).unwrap_or(path);
+    let mut components = relative_path.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(directory)), Some(_)) => directory.to_string_lossy().into_owned(),
+        _ => ".".into(),
+    }
+}
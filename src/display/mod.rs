@@ -1,20 +1,71 @@
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
+use std::path::Path;
 
-use crate::{context::AppContext, core::CompositionEntry};
+use crate::{
+    context::{AppContext, cli::Metric, config::Tracked},
+    core::CompositionEntry,
+};
 
 pub mod spinner;
 
+mod diff;
+pub use diff::display_composition_diff;
+
+mod age;
+pub use age::display_code_age;
+
+mod authors;
+pub use authors::display_author_composition;
+
+mod categories;
+pub use categories::display_category_composition;
+
+mod files;
+pub use files::display_file_line_counts;
+
+mod groups;
+pub use groups::{
+    display_directory_composition, display_license_composition, display_owner_composition,
+    display_package_composition,
+};
+
+mod history;
+pub use history::{display_composition_history, write_history_svg};
+
+mod hotspots;
+pub use hotspots::display_hotspots;
+
+mod tree;
+pub use tree::display_composition_tree;
+
+mod skipped;
+pub use skipped::display_skipped;
+
+// languages beyond this are folded into an "Other" column in wide terminal tables
+const MAX_TABLE_LANGUAGES: usize = 8;
+
+type LineCountFn = fn(&CompositionEntry) -> usize;
+
 pub fn display_composition(
     app_context: &AppContext,
     composition_entries: &mut Vec<CompositionEntry>,
 ) {
-    // reorder based on line count
-    composition_entries.sort_by_key(|e| std::cmp::Reverse(e.line_count));
+    let (metric_value, metric_unit) = metric_column(app_context.metric);
+
+    // reorder based on the selected metric
+    composition_entries.sort_by_key(|e| std::cmp::Reverse(metric_value(e)));
 
-    let total_lines: usize = composition_entries.iter().map(|e| e.line_count).sum();
+    // excluded categories are still listed, but do not take a share
+    let config = &app_context.config;
+    let total: usize = composition_entries
+        .iter()
+        .filter(|e| config.counts_towards_percentage(&e.tracked))
+        .map(metric_value)
+        .sum();
     composition_entries.iter_mut().for_each(|e| {
-        let percentage = (e.line_count as f32 / total_lines as f32) * 100.0;
-        e.percentage = percentage;
+        if config.counts_towards_percentage(&e.tracked) {
+            e.percentage = (metric_value(e) as f32 / total.max(1) as f32) * 100.0;
+        }
     });
 
     let max_display_width = composition_entries
@@ -25,9 +76,46 @@ pub fn display_composition(
 
     let max_lines_width = composition_entries
         .iter()
-        .map(|e| e.line_count.ilog10() + 1)
+        .map(|e| digit_count(metric_value(e)))
         .max()
-        .unwrap_or(10) as usize;
+        .unwrap_or(10);
+
+    let mut columns: Vec<(&str, LineCountFn)> = Vec::new();
+
+    if app_context.metric == Metric::Lines {
+        // first-party lines are split into source and test once any test code was found
+        if composition_entries.iter().any(|e| e.test_line_count > 0) {
+            columns.push(("source", |e| e.line_count - e.test_line_count));
+            columns.push(("test", |e| e.test_line_count));
+        }
+
+        // secondary buckets are only shown when at least one entry has lines in them
+        let buckets: [(&str, LineCountFn); 2] = [
+            ("generated", |e| e.generated_line_count),
+            ("vendored", |e| e.vendored_line_count),
+        ];
+        for (label, value) in buckets {
+            if composition_entries.iter().any(|e| value(e) > 0) {
+                columns.push((label, value));
+            }
+        }
+    } else {
+        // other metrics keep the counted lines next to them for reference
+        columns.push(("lines", |e| e.line_count));
+    }
+
+    let extra_columns: Vec<(&str, LineCountFn, usize)> = columns
+        .into_iter()
+        .map(|(label, value)| {
+            let width = composition_entries
+                .iter()
+                .map(value)
+                .map(digit_count)
+                .max()
+                .unwrap_or(1);
+            (label, value, width)
+        })
+        .collect();
 
     let max_percentage = composition_entries
         .iter()
@@ -35,7 +123,11 @@ pub fn display_composition(
         .fold(0_f32, f32::max);
 
     for entry in composition_entries {
-        if entry.line_count == 0 {
+        let is_empty = match app_context.metric {
+            Metric::Lines => entry.is_empty(),
+            _ => metric_value(entry) == 0,
+        };
+        if is_empty {
             continue;
         }
 
@@ -48,31 +140,98 @@ pub fn display_composition(
             entry.percentage / 100.0
         };
 
-        let bar_width = (raw * app_context.scale_bar * 100.0).round() as usize;
-        let bar = "█".repeat(bar_width);
-        let bar = match (app_context.config.use_color, &entry.tracked.color) {
-            (true, Some(hex)) => {
-                if let Some((r, g, b)) = hex_to_rgb(hex) {
-                    bar.truecolor(r, g, b)
-                } else {
-                    bar.normal()
-                }
-            }
-            (true, None) | (false, _) => bar.normal(),
+        let bar = bar(app_context, &entry.tracked, raw);
+
+        // generated and vendored lines are shown next to, but never counted towards, the first-party lines
+        let extra: String = extra_columns
+            .iter()
+            .map(|(label, value, width)| {
+                format!(" {:>width$} {} |", value(entry), label, width = width)
+            })
+            .collect();
+
+        let share = if config.counts_towards_percentage(&entry.tracked) {
+            format!("{:>5.2}%", entry.percentage)
+        } else {
+            format!("{:>6}", "-")
         };
 
         println!(
-            "{:>width_display$} | {:>width_lines$} lines | {:>5.2}% | {}",
+            "{:>width_display$} | {:>width_lines$} {} |{} {} | {}",
             entry.tracked.display,
-            entry.line_count,
-            entry.percentage,
+            metric_value(entry),
+            metric_unit,
+            extra,
+            share,
             bar,
             width_display = max_display_width + 1,
             width_lines = max_lines_width,
         );
+
+        // a language with a single extension would only repeat its own row
+        if app_context.expand && entry.extension_line_counts.len() > 1 {
+            let blank_extra: String = extra_columns
+                .iter()
+                .map(|(label, _, width)| {
+                    format!(" {:>width$} |", "", width = width + 1 + label.len())
+                })
+                .collect();
+
+            for (ext, lines) in &entry.extension_line_counts {
+                let share = (*lines as f32 / entry.line_count.max(1) as f32) * 100.0;
+                println!(
+                    "{} | {:>width_lines$} lines |{} {:>5.2}% of {}",
+                    extension_label(&entry.tracked.display, ext, max_display_width + 1),
+                    lines,
+                    blank_extra,
+                    share,
+                    entry.tracked.display,
+                    width_lines = max_lines_width,
+                );
+            }
+        }
+    }
+}
+
+// `.ext` indented below the start of the right-aligned language name, kept within `width`
+fn extension_label(display: &str, ext: &str, width: usize) -> String {
+    let label = format!(".{}", ext);
+    let indent = (width.saturating_sub(display.len()) + 2).min(width.saturating_sub(label.len()));
+    format!("{:indent$}{:<rest$}", "", label, rest = width - indent)
+}
+
+// the value that drives sorting, percentages and bars, and the unit it is printed with
+fn metric_column(metric: Metric) -> (LineCountFn, &'static str) {
+    match metric {
+        Metric::Lines => (|e| e.line_count, "lines"),
+        Metric::RawLines => (|e| e.raw_line_count, "raw lines"),
+        Metric::Code => (|e| e.code_line_count, "code lines"),
+        Metric::Comments => (|e| e.comment_line_count, "comment lines"),
+        Metric::Bytes => (|e| e.byte_count, "bytes"),
+        Metric::Files => (|e| e.file_count, "files"),
+    }
+}
+
+// a bar of `fraction` times the full width, in the language color when colors are enabled
+fn bar(app_context: &AppContext, tracked: &Tracked, fraction: f32) -> ColoredString {
+    let bar_width = (fraction * app_context.scale_bar * 100.0).round() as usize;
+    let bar = "█".repeat(bar_width);
+    match (app_context.config.use_color, &tracked.color) {
+        (true, Some(hex)) => {
+            if let Some((r, g, b)) = hex_to_rgb(hex) {
+                bar.truecolor(r, g, b)
+            } else {
+                bar.normal()
+            }
+        }
+        (true, None) | (false, _) => bar.normal(),
     }
 }
 
+fn digit_count(value: usize) -> usize {
+    value.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
 fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
     let hex = hex.trim_start_matches('#');
     if hex.len() != 6 {
@@ -85,3 +244,37 @@ fn hex_to_rgb(hex: &str) -> Option<(u8, u8, u8)> {
 
     Some((r, g, b))
 }
+
+// `path` relative to the scanned directory
+fn relative_path(app_context: &AppContext, path: &Path) -> String {
+    path.strip_prefix(&app_context.path)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod display_tests {
+    use super::*;
+
+    #[test]
+    fn extension_rows_are_indented_below_the_language_name() {
+        assert_eq!(extension_label("TypeScript", "tsx", 12), "    .tsx    ");
+        // short names leave no room to indent, so the label ends with the column
+        assert_eq!(extension_label("Go", "go", 12), "         .go");
+    }
+
+    #[test]
+    fn long_extensions_stay_within_the_column() {
+        assert_eq!(extension_label("Go", "markdown", 12), "   .markdown");
+        assert_eq!(extension_label("Go", "mdx", 4), ".mdx");
+    }
+}
@@ -0,0 +1,109 @@
+use colored::Colorize;
+use std::collections::BTreeMap;
+
+use super::relative_path;
+use crate::{context::AppContext, core::SkippedFile};
+
+pub fn display_skipped(app_context: &AppContext, skipped: &[SkippedFile]) {
+    if skipped.is_empty() {
+        return;
+    }
+
+    println!("{}", summary(skipped).yellow());
+
+    if !app_context.show_skipped {
+        return;
+    }
+
+    for row in skipped_rows(app_context, skipped) {
+        println!("  {}", row);
+    }
+}
+
+// the number of skipped files, broken down per reason in the order reasons are declared
+fn summary(skipped: &[SkippedFile]) -> String {
+    let mut counts_by_reason = BTreeMap::new();
+    for file in skipped {
+        *counts_by_reason.entry(file.reason).or_insert(0_usize) += 1;
+    }
+
+    let breakdown = counts_by_reason
+        .iter()
+        .map(|(reason, count)| format!("{} {}", count, reason))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "{} {} skipped ({})",
+        skipped.len(),
+        if skipped.len() == 1 { "file" } else { "files" },
+        breakdown
+    )
+}
+
+// skipped files grouped like the summary, then by path, so the listing is stable
+fn skipped_rows(app_context: &AppContext, skipped: &[SkippedFile]) -> Vec<String> {
+    let mut rows: Vec<(_, String)> = skipped
+        .iter()
+        .map(|file| (file.reason, relative_path(app_context, &file.path)))
+        .collect();
+    rows.sort();
+
+    rows.into_iter()
+        .map(|(reason, path)| format!("{} ({})", path, reason))
+        .collect()
+}
+
+#[cfg(test)]
+mod skipped_tests {
+    use super::*;
+    use crate::core::{get_all_entries, get_composition, git::test_repository::TestRepository};
+
+    fn sample() -> TestRepository {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn main() {}\n");
+        repository.write("z.js", "\0binary\n");
+        repository.write("a.js", "\0binary\n");
+        repository.write("app.js", &format!("{}\n", "a=1;b=2;".repeat(200)));
+        repository.write("api.generated.ts", "export const a = 1;\n");
+        repository
+    }
+
+    fn skipped(app_context: &AppContext) -> Vec<SkippedFile> {
+        let files = get_all_entries(app_context).unwrap();
+        get_composition(app_context, files).skipped
+    }
+
+    #[test]
+    fn summary_counts_each_reason() {
+        let repository = sample();
+
+        let app_context = repository.app_context(&[]);
+        assert_eq!(
+            summary(&skipped(&app_context)),
+            "3 files skipped (2 binary, 1 minified)"
+        );
+
+        let app_context = repository.app_context(&["--ignore-generated-files"]);
+        assert_eq!(
+            summary(&skipped(&app_context)),
+            "4 files skipped (2 binary, 1 minified, 1 generated)"
+        );
+    }
+
+    #[test]
+    fn skipped_files_are_listed_by_reason_then_path() {
+        let repository = sample();
+        let app_context = repository.app_context(&["--show-skipped", "--ignore-generated-files"]);
+
+        assert_eq!(
+            skipped_rows(&app_context, &skipped(&app_context)),
+            [
+                "a.js (binary)",
+                "z.js (binary)",
+                "app.js (minified)",
+                "api.generated.ts (generated)",
+            ]
+        );
+    }
+}
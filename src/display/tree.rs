@@ -0,0 +1,265 @@
+use colored::{ColoredString, Colorize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use super::hex_to_rgb;
+use crate::{
+    context::{AppContext, config::Tracked},
+    core::DirectoryComposition,
+};
+
+// width of the inline language mix bar
+const MIX_BAR_WIDTH: usize = 20;
+
+// without colors, languages are told apart by their shading instead
+const MIX_BAR_SHADES: [&str; 4] = ["█", "▓", "▒", "░"];
+
+#[derive(Default)]
+struct TreeNode<'a> {
+    line_count: usize,
+    lines_by_tracked: HashMap<&'a str, (&'a Tracked, usize)>,
+}
+
+pub fn display_composition_tree(app_context: &AppContext, directories: &[DirectoryComposition]) {
+    let nodes = tree_nodes(directories);
+
+    let Some(root) = nodes.get(Path::new("")) else {
+        println!("No counted lines found");
+        return;
+    };
+
+    // a single palette index per language keeps shades consistent across rows
+    let mut languages: Vec<(&str, usize)> = root
+        .lines_by_tracked
+        .iter()
+        .map(|(display, (_, lines))| (*display, *lines))
+        .collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let language_order: HashMap<&str, usize> = languages
+        .iter()
+        .enumerate()
+        .map(|(index, (display, _))| (*display, index))
+        .collect();
+
+    let printer = TreePrinter {
+        app_context,
+        nodes: &nodes,
+        language_order: &language_order,
+    };
+
+    println!(
+        "{} {} lines {}",
+        app_context.path.display(),
+        root.line_count,
+        printer.mix_bar(root)
+    );
+    printer.print_children(Path::new(""), "", 1);
+}
+
+// each directory's lines are added to itself and all of its ancestors, up to the root at ""
+fn tree_nodes(directories: &[DirectoryComposition]) -> BTreeMap<PathBuf, TreeNode<'_>> {
+    let mut nodes: BTreeMap<PathBuf, TreeNode> = BTreeMap::new();
+    for directory in directories {
+        let mut ancestor = PathBuf::new();
+        let ancestors = std::iter::once(None).chain(directory.path.components().map(Some));
+        for component in ancestors {
+            if let Some(component) = component {
+                ancestor.push(component);
+            }
+
+            let node = nodes.entry(ancestor.clone()).or_default();
+            for entry in directory.entries.iter().filter(|e| e.line_count > 0) {
+                node.line_count += entry.line_count;
+                node.lines_by_tracked
+                    .entry(&entry.tracked.display)
+                    .or_insert((&entry.tracked, 0))
+                    .1 += entry.line_count;
+            }
+        }
+    }
+
+    nodes
+}
+
+struct TreePrinter<'a> {
+    app_context: &'a AppContext,
+    nodes: &'a BTreeMap<PathBuf, TreeNode<'a>>,
+    language_order: &'a HashMap<&'a str, usize>,
+}
+
+impl TreePrinter<'_> {
+    fn print_children(&self, parent: &Path, prefix: &str, depth: usize) {
+        if self.app_context.tree_depth.is_some_and(|max| depth > max) {
+            return;
+        }
+
+        // direct children above the cutoff, largest first
+        let mut children: Vec<(&PathBuf, &TreeNode)> = self
+            .nodes
+            .iter()
+            .filter(|(path, _)| path.parent() == Some(parent) && !path.as_os_str().is_empty())
+            .filter(|(_, node)| {
+                node.line_count > 0 && node.line_count >= self.app_context.min_lines
+            })
+            .collect();
+        children.sort_by(|(a_path, a), (b_path, b)| {
+            b.line_count.cmp(&a.line_count).then(a_path.cmp(b_path))
+        });
+
+        for (index, (path, node)) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            let (branch, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            println!(
+                "{}{}{} {} lines {}",
+                prefix,
+                branch,
+                name,
+                node.line_count,
+                self.mix_bar(node)
+            );
+
+            self.print_children(path, &format!("{}{}", prefix, indent), depth + 1);
+        }
+    }
+
+    // a fixed width bar split into one segment per language, in the same order for every node
+    fn mix_bar(&self, node: &TreeNode) -> String {
+        let mut segments: Vec<(&Tracked, usize)> =
+            node.lines_by_tracked.values().copied().collect();
+        segments.sort_by_key(|(tracked, _)| {
+            self.language_order
+                .get(tracked.display.as_str())
+                .copied()
+                .unwrap_or(usize::MAX)
+        });
+
+        // cumulative rounding keeps the total width exact
+        let mut bar = String::new();
+        let mut cumulative_lines = 0;
+        let mut drawn = 0;
+        for (tracked, lines) in segments {
+            cumulative_lines += lines;
+            let end =
+                (cumulative_lines * MIX_BAR_WIDTH + node.line_count / 2) / node.line_count.max(1);
+            let width = end.saturating_sub(drawn);
+            drawn = end;
+
+            let index = self
+                .language_order
+                .get(tracked.display.as_str())
+                .copied()
+                .unwrap_or(0);
+            bar.push_str(&self.segment(tracked, index, width).to_string());
+        }
+
+        bar
+    }
+
+    fn segment(&self, tracked: &Tracked, index: usize, width: usize) -> ColoredString {
+        if !self.app_context.config.use_color {
+            return MIX_BAR_SHADES[index % MIX_BAR_SHADES.len()]
+                .repeat(width)
+                .normal();
+        }
+
+        // languages without a color still need to be told apart
+        match tracked.color.as_deref().and_then(hex_to_rgb) {
+            Some((r, g, b)) => "█".repeat(width).truecolor(r, g, b),
+            None => MIX_BAR_SHADES[index % MIX_BAR_SHADES.len()]
+                .repeat(width)
+                .normal(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tree_tests {
+    use super::*;
+    use crate::core::{get_all_entries, get_composition, git::test_repository::TestRepository};
+
+    fn directories(app_context: &AppContext) -> Vec<DirectoryComposition> {
+        let files = get_all_entries(app_context).unwrap();
+        get_composition(app_context, files).directories
+    }
+
+    #[test]
+    fn directory_lines_roll_up_into_their_ancestors() {
+        let repository = TestRepository::new();
+        repository.write("build.py", "one = 1\n");
+        repository.write("src/main.rs", "fn one() {}\nfn two() {}\n");
+        repository.write("src/core/git/mod.rs", "fn three() {}\n");
+        let app_context = repository.app_context(&["--tree"]);
+
+        let directories = directories(&app_context);
+        let nodes = tree_nodes(&directories);
+
+        let line_counts: Vec<_> = nodes
+            .iter()
+            .map(|(path, node)| (path.to_str().unwrap(), node.line_count))
+            .collect();
+        assert_eq!(
+            line_counts,
+            [("", 4), ("src", 3), ("src/core", 1), ("src/core/git", 1)]
+        );
+        let root = &nodes[Path::new("")];
+        assert_eq!(root.lines_by_tracked["Rust"].1, 3);
+        assert_eq!(root.lines_by_tracked["Python"].1, 1);
+    }
+
+    #[test]
+    fn mix_bars_keep_their_width_when_shares_round() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\n");
+        repository.write("main.py", "one = 1\n");
+        repository.write("main.go", "var one = 1\n");
+        let app_context = repository.app_context(&["--tree", "--use-color=false"]);
+
+        let directories = directories(&app_context);
+        let nodes = tree_nodes(&directories);
+        let language_order = HashMap::from([("Go", 0), ("Python", 1), ("Rust", 2)]);
+        let printer = TreePrinter {
+            app_context: &app_context,
+            nodes: &nodes,
+            language_order: &language_order,
+        };
+
+        // a third each rounds to 7, 6 and 7 characters
+        assert_eq!(
+            printer.mix_bar(&nodes[Path::new("")]),
+            "███████▓▓▓▓▓▓▒▒▒▒▒▒▒"
+        );
+    }
+
+    #[test]
+    fn colorless_languages_are_shaded_when_color_is_on() {
+        let repository = TestRepository::new();
+        repository.write("main.rs", "fn one() {}\n");
+        let app_context = repository.app_context(&["--tree", "--use-color=true"]);
+
+        let directories = directories(&app_context);
+        let nodes = tree_nodes(&directories);
+        let language_order = HashMap::new();
+        let printer = TreePrinter {
+            app_context: &app_context,
+            nodes: &nodes,
+            language_order: &language_order,
+        };
+
+        let mut tracked = app_context.config.tracked[0].clone();
+        tracked.color = None;
+        assert_eq!(printer.segment(&tracked, 0, 2).to_string(), "██");
+        assert_eq!(printer.segment(&tracked, 1, 2).to_string(), "▓▓");
+        assert_eq!(printer.segment(&tracked, 5, 2).to_string(), "▓▓");
+    }
+}
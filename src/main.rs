@@ -2,21 +2,38 @@ mod context;
 mod core;
 mod display;
 
+use chrono::{Months, NaiveDate, Utc};
 use clap::Parser;
 use colored::Colorize;
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 
 use crate::{
-    context::{AppContext, cli::Cli},
-    core::{get_all_entries, get_composition},
-    display::{display_composition, spinner},
+    context::{
+        AppContext,
+        cli::{AgePeriod, Cli, Command, HistoryStep, OutputFormat},
+    },
+    core::{
+        get_all_entries, get_category_composition, get_composition, get_license_composition,
+        get_owner_composition, get_package_composition,
+        git::{
+            AgeBucket, SampleStep, get_author_composition, get_code_age, get_composition_diff,
+            get_composition_history, get_hotspots,
+        },
+    },
+    display::{
+        display_author_composition, display_category_composition, display_code_age,
+        display_composition, display_composition_diff, display_composition_history,
+        display_composition_tree, display_directory_composition, display_file_line_counts,
+        display_hotspots, display_license_composition, display_owner_composition,
+        display_package_composition, display_skipped, spinner, write_history_svg,
+    },
 };
 
 fn main() {
     let cli = Cli::parse();
     let app_context = AppContext::from_cli(cli);
     if !app_context.config_loaded {
-        println!(
+        eprintln!(
             "{}",
             "Warning: Missing or invalid config.toml file. Using default settings."
                 .yellow()
@@ -24,15 +41,46 @@ fn main() {
         );
     }
 
+    match &app_context.command {
+        Some(Command::Diff { range }) => run_diff(&app_context, range),
+        Some(Command::History {
+            since,
+            until,
+            step,
+            rev,
+            format,
+            svg,
+        }) => run_history(
+            &app_context,
+            rev,
+            *since,
+            *until,
+            *step,
+            *format,
+            svg.as_deref(),
+        ),
+        Some(Command::Authors { rev, format }) => run_authors(&app_context, rev, *format),
+        Some(Command::Age { by, rev, format }) => run_age(&app_context, rev, *by, *format),
+        Some(Command::Hotspots {
+            since,
+            rev,
+            top,
+            format,
+        }) => run_hotspots(&app_context, rev, *since, *top, *format),
+        None => run_composition(&app_context),
+    }
+}
+
+fn run_composition(app_context: &AppContext) {
     let start = Instant::now();
 
     spinner::start("Walking directory...");
-    let entries = get_all_entries(&app_context);
-    let entries_len = entries.len();
+    let files = get_all_entries(app_context).unwrap_or_else(|error| exit_with_error(error));
+    let files_len = files.len();
     spinner::end();
 
     spinner::start("Calculating composition...");
-    let mut composition = get_composition(&app_context, entries);
+    let composition = get_composition(app_context, files);
     spinner::end();
 
     let elapsed = start.elapsed();
@@ -41,11 +89,247 @@ fn main() {
         "{}",
         format!(
             "Parsed {} files in {:.6} seconds",
-            entries_len,
+            files_len,
             elapsed.as_secs_f64()
         )
         .bold()
     );
 
-    display_composition(&app_context, &mut composition);
+    display_skipped(app_context, &composition.skipped);
+
+    if app_context.list_files {
+        display_file_line_counts(app_context, &composition.files, &composition.entries);
+        return;
+    }
+
+    if app_context.by_category {
+        let mut categories = get_category_composition(&composition.entries);
+        display_category_composition(app_context, &mut categories);
+        return;
+    }
+
+    if app_context.by_license {
+        let licenses = get_license_composition(&composition.files);
+        display_license_composition(app_context, &licenses, &composition.entries);
+        return;
+    }
+
+    if app_context.by_owner {
+        let owners = get_owner_composition(app_context, &composition.files)
+            .unwrap_or_else(|error| exit_with_error(error));
+        display_owner_composition(app_context, &owners, &composition.entries);
+        return;
+    }
+
+    if app_context.by_package {
+        let packages = get_package_composition(app_context, &composition.directories)
+            .unwrap_or_else(|error| exit_with_error(error));
+        display_package_composition(app_context, &packages, &composition.entries);
+        return;
+    }
+
+    if app_context.tree {
+        display_composition_tree(app_context, &composition.directories);
+        return;
+    }
+
+    if app_context.directory_depth.is_some() {
+        display_directory_composition(app_context, &composition.directories, &composition.entries);
+        return;
+    }
+
+    let mut entries = composition.entries;
+    display_composition(app_context, &mut entries);
+}
+
+fn run_diff(app_context: &AppContext, range: &str) {
+    let start = Instant::now();
+
+    spinner::start("Comparing revisions...");
+    let mut entries =
+        get_composition_diff(app_context, range).unwrap_or_else(|error| exit_with_error(error));
+    spinner::end();
+
+    let elapsed = start.elapsed();
+
+    println!(
+        "{}",
+        format!("Compared {} in {:.6} seconds", range, elapsed.as_secs_f64()).bold()
+    );
+
+    display_composition_diff(app_context, &mut entries);
+}
+
+fn run_history(
+    app_context: &AppContext,
+    revision: &str,
+    since: NaiveDate,
+    until: Option<NaiveDate>,
+    step: HistoryStep,
+    format: OutputFormat,
+    svg: Option<&Path>,
+) {
+    // machine-readable output is kept free of progress and timing messages
+    let is_table = format == OutputFormat::Table;
+    let start = Instant::now();
+
+    if is_table {
+        spinner::start("Sampling history...");
+    }
+    let samples = get_composition_history(app_context, revision, since, until, sample_step(step))
+        .unwrap_or_else(|error| exit_with_error(error));
+    if is_table {
+        spinner::end();
+    }
+
+    let elapsed = start.elapsed();
+
+    if is_table {
+        println!(
+            "{}",
+            format!(
+                "Took {} samples in {:.6} seconds",
+                samples.len(),
+                elapsed.as_secs_f64()
+            )
+            .bold()
+        );
+    }
+
+    display_composition_history(&samples, format);
+
+    if let Some(svg) = svg
+        && let Err(error) = write_history_svg(&samples, svg)
+    {
+        exit_with_error(format!("could not write '{}': {}", svg.display(), error));
+    }
+}
+
+fn run_authors(app_context: &AppContext, revision: &str, format: OutputFormat) {
+    // machine-readable output is kept free of progress and timing messages
+    let is_table = format == OutputFormat::Table;
+    let start = Instant::now();
+
+    if is_table {
+        spinner::start("Blaming files...");
+    }
+    let mut entries = get_author_composition(app_context, revision)
+        .unwrap_or_else(|error| exit_with_error(error));
+    if is_table {
+        spinner::end();
+    }
+
+    let elapsed = start.elapsed();
+
+    if is_table {
+        println!(
+            "{}",
+            format!(
+                "Blamed {} at {} in {:.6} seconds",
+                app_context.path.display(),
+                revision,
+                elapsed.as_secs_f64()
+            )
+            .bold()
+        );
+    }
+
+    display_author_composition(&mut entries, format);
+}
+
+fn run_age(app_context: &AppContext, revision: &str, period: AgePeriod, format: OutputFormat) {
+    // machine-readable output is kept free of progress and timing messages
+    let is_table = format == OutputFormat::Table;
+    let start = Instant::now();
+
+    if is_table {
+        spinner::start("Blaming files...");
+    }
+    let mut entries = get_code_age(app_context, revision, age_bucket(period))
+        .unwrap_or_else(|error| exit_with_error(error));
+    if is_table {
+        spinner::end();
+    }
+
+    let elapsed = start.elapsed();
+
+    if is_table {
+        println!(
+            "{}",
+            format!(
+                "Blamed {} at {} in {:.6} seconds",
+                app_context.path.display(),
+                revision,
+                elapsed.as_secs_f64()
+            )
+            .bold()
+        );
+    }
+
+    display_code_age(app_context, &mut entries, format);
+}
+
+fn run_hotspots(
+    app_context: &AppContext,
+    revision: &str,
+    since: Option<NaiveDate>,
+    top: usize,
+    format: OutputFormat,
+) {
+    let since = since.unwrap_or_else(|| {
+        let today = Utc::now().date_naive();
+        today.checked_sub_months(Months::new(12)).unwrap_or(today)
+    });
+
+    // machine-readable output is kept free of progress and timing messages
+    let is_table = format == OutputFormat::Table;
+    let start = Instant::now();
+
+    if is_table {
+        spinner::start("Reading history...");
+    }
+    let mut hotspots =
+        get_hotspots(app_context, revision, since).unwrap_or_else(|error| exit_with_error(error));
+    if is_table {
+        spinner::end();
+    }
+
+    let elapsed = start.elapsed();
+
+    if is_table {
+        println!(
+            "{}",
+            format!(
+                "Read history since {} in {:.6} seconds",
+                since,
+                elapsed.as_secs_f64()
+            )
+            .bold()
+        );
+    }
+
+    display_hotspots(app_context, &mut hotspots, top, format);
+}
+
+fn age_bucket(period: AgePeriod) -> AgeBucket {
+    match period {
+        AgePeriod::Year => AgeBucket::Year,
+        AgePeriod::Quarter => AgeBucket::Quarter,
+    }
+}
+
+fn sample_step(step: HistoryStep) -> SampleStep {
+    match step {
+        HistoryStep::Daily => SampleStep::Days(1),
+        HistoryStep::Weekly => SampleStep::Days(7),
+        HistoryStep::Monthly => SampleStep::Months(1),
+        HistoryStep::Quarterly => SampleStep::Months(3),
+        HistoryStep::Yearly => SampleStep::Months(12),
+    }
+}
+
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+    spinner::end();
+    eprintln!("{}", format!("Error: {}", error).red().bold());
+    std::process::exit(1);
 }